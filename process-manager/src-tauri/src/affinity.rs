@@ -0,0 +1,309 @@
+use sysinfo::Pid;
+#[cfg(not(target_os = "linux"))]
+use sysinfo::{CpuRefreshKind, RefreshKind, System};
+
+use crate::process::parse_pid;
+use crate::setting::SettingChange;
+
+/// Parses a kernel CPU list (e.g., "0-3,6,8-9")
+///
+/// Returns the sorted CPU numbers or a String error if the list is malformed
+#[cfg(target_os = "linux")]
+fn parse_cpu_list(list: &str) -> Result<Vec<usize>, String> {
+    let invalid = || format!("Invalid CPU list ({list})");
+    let mut cpus = Vec::new();
+    for range in list.trim().split(',').filter(|range| !range.is_empty()) {
+        let (first, last) = range.split_once('-').unwrap_or((range, range));
+        let first = first.parse::<usize>().map_err(|_| invalid())?;
+        let last = last.parse::<usize>().map_err(|_| invalid())?;
+        if first > last {
+            return Err(invalid());
+        }
+        cpus.extend(first..=last);
+    }
+    cpus.sort_unstable();
+    Ok(cpus)
+}
+
+/// Lists the CPUs that are online
+///
+/// Returns the sorted CPU numbers, which can have gaps when CPUs are taken offline, or a String
+/// error if they can't be read
+#[cfg(target_os = "linux")]
+fn online_cpus() -> Result<Vec<usize>, String> {
+    let list = std::fs::read_to_string("/sys/devices/system/cpu/online")
+        .map_err(|e| format!("Failed to read the online CPUs: {e}"))?;
+    parse_cpu_list(&list)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn online_cpus() -> Result<Vec<usize>, String> {
+    let sys =
+        System::new_with_specifics(RefreshKind::nothing().with_cpu(CpuRefreshKind::nothing()));
+    Ok((0..sys.cpus().len()).collect())
+}
+
+/// Validates a CPU set received from the frontend
+///
+/// `cpus` holds CPU numbers, as in the `cpuN` names of `System::cpus()`. Duplicates are dropped
+///
+/// On Linux the numbers are checked against /sys/devices/system/cpu/online rather than
+/// `System::cpus()`, which only lists the online CPUs. Once a CPU is taken offline, positions in
+/// that list no longer match the CPU numbers that sched_setaffinity takes
+///
+/// Returns the sorted CPU set or a String error if it is empty or names a CPU that isn't online
+fn validate_cpu_set(mut cpus: Vec<usize>) -> Result<Vec<usize>, String> {
+    cpus.sort_unstable();
+    cpus.dedup();
+    if cpus.is_empty() {
+        return Err("CPU set must contain at least one CPU".to_owned());
+    }
+
+    let online = online_cpus()?;
+    if let Some(cpu) = cpus.iter().find(|cpu| online.binary_search(cpu).is_err()) {
+        let online = online
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        return Err(format!(
+            "Invalid CPU ({cpu}). Expected one of the online CPUs: {online}"
+        ));
+    }
+    Ok(cpus)
+}
+
+/// Reads the CPU affinity of a thread
+///
+/// Returns the sorted CPU numbers or the errno as an `io::Error`
+#[cfg(target_os = "linux")]
+fn get_affinity(tid: u32) -> Result<Vec<usize>, std::io::Error> {
+    // SAFETY: cpu_set_t is a plain bitmask, so all zeroes is a valid (empty) set, and
+    // sched_getaffinity writes at most `size_of::<cpu_set_t>()` bytes into it
+    unsafe {
+        let mut set = std::mem::zeroed::<libc::cpu_set_t>();
+        if libc::sched_getaffinity(
+            tid as libc::pid_t,
+            std::mem::size_of::<libc::cpu_set_t>(),
+            &mut set,
+        ) != 0
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok((0..libc::CPU_SETSIZE as usize)
+            .filter(|cpu| libc::CPU_ISSET(*cpu, &set))
+            .collect())
+    }
+}
+
+/// Reads the CPU affinity of a process' main thread
+///
+/// Returns the sorted CPU numbers or a String error
+#[cfg(target_os = "linux")]
+pub(crate) fn read_cpu_affinity(pid: Pid) -> Result<Vec<usize>, String> {
+    get_affinity(pid.as_u32()).map_err(|error| {
+        crate::priority::priority_error(error, pid.as_u32(), "CPU affinity", false)
+    })
+}
+
+/// Sets the CPU affinity of a thread
+///
+/// `cpus` must hold CPU numbers below `CPU_SETSIZE`
+///
+/// Returns a Unit Type or the errno as an `io::Error`
+#[cfg(target_os = "linux")]
+fn set_affinity(tid: u32, cpus: &[usize]) -> Result<(), std::io::Error> {
+    // SAFETY: all zeroes is an empty cpu_set_t, every index is below CPU_SETSIZE and the set
+    // passed to sched_setaffinity is of the size passed in
+    unsafe {
+        let mut set = std::mem::zeroed::<libc::cpu_set_t>();
+        for cpu in cpus {
+            libc::CPU_SET(*cpu, &mut set);
+        }
+        if libc::sched_setaffinity(
+            tid as libc::pid_t,
+            std::mem::size_of::<libc::cpu_set_t>(),
+            &set,
+        ) != 0
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Pins a process or every one of its threads to a set of CPUs
+///
+/// Affinity is kept per thread, so without `all_threads` only the main thread (and threads it
+/// creates afterwards) moves. Threads that exit midway are skipped. If a thread can't be changed,
+/// the threads changed before it are restored
+///
+/// Returns a vector of `SettingChange`s, main thread first, or a String error
+#[cfg(target_os = "linux")]
+pub(crate) fn pin_to_cpus(
+    pid: Pid,
+    cpus: Vec<usize>,
+    all_threads: bool,
+) -> Result<Vec<SettingChange<Vec<usize>>>, String> {
+    use crate::priority::{priority_error, roll_back, target_threads};
+
+    let cpus = validate_cpu_set(cpus)?;
+    if let Some(cpu) = cpus.iter().find(|cpu| **cpu >= libc::CPU_SETSIZE as usize) {
+        return Err(format!(
+            "CPU {cpu} is beyond the CPUs an affinity mask can hold"
+        ));
+    }
+
+    let mut changes = Vec::new();
+    for tid in target_threads(pid, all_threads)? {
+        let is_main = tid == pid.as_u32();
+        let before = match get_affinity(tid) {
+            Ok(before) => before,
+            Err(error) if !is_main && error.raw_os_error() == Some(libc::ESRCH) => continue,
+            Err(error) => {
+                let error = priority_error(error, tid, "CPU affinity", false);
+                return Err(roll_back(
+                    changes,
+                    |tid, cpus: &Vec<usize>| set_affinity(tid, cpus),
+                    error,
+                ));
+            }
+        };
+
+        if let Err(error) = set_affinity(tid, &cpus) {
+            if !is_main && error.raw_os_error() == Some(libc::ESRCH) {
+                continue;
+            }
+            let error = priority_error(error, tid, "CPU affinity", false);
+            return Err(roll_back(
+                changes,
+                |tid, cpus: &Vec<usize>| set_affinity(tid, cpus),
+                error,
+            ));
+        }
+        changes.push(SettingChange {
+            id: tid.to_string(),
+            before,
+            after: get_affinity(tid).unwrap_or_else(|_| cpus.clone()),
+        });
+    }
+    Ok(changes)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn read_cpu_affinity(_pid: Pid) -> Result<Vec<usize>, String> {
+    Err("CPU affinity is only supported on Linux".to_owned())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn pin_to_cpus(
+    _pid: Pid,
+    cpus: Vec<usize>,
+    _all_threads: bool,
+) -> Result<Vec<SettingChange<Vec<usize>>>, String> {
+    validate_cpu_set(cpus)?;
+    Err("CPU affinity is only supported on Linux".to_owned())
+}
+
+/// Gets the CPUs a process may run on
+///
+/// `id` is the Pid of the process. Only the main thread's affinity is read
+///
+/// Returns the sorted CPU numbers, as in the `cpuN` names of `System::cpus()`, if successful and a
+/// String error if unsuccessful
+#[tauri::command]
+pub async fn get_cpu_affinity(id: &str) -> Result<Vec<usize>, String> {
+    read_cpu_affinity(parse_pid(id)?)
+}
+
+/// Restricts a process to a set of CPUs, like `taskset -p`
+///
+/// `id` is the Pid of the process and `cpus` holds CPU numbers, as in the `cpuN` names of
+/// `System::cpus()`, which must be online
+///
+/// `all_threads` applies the change to every thread instead of only the main thread, like
+/// `taskset -a`
+///
+/// Returns the affinity of each changed thread before and after the change if successful and a
+/// String error if unsuccessful
+#[tauri::command]
+pub async fn set_cpu_affinity(
+    id: &str,
+    cpus: Vec<usize>,
+    all_threads: Option<bool>,
+) -> Result<Vec<SettingChange<Vec<usize>>>, String> {
+    pin_to_cpus(parse_pid(id)?, cpus, all_threads.unwrap_or(false))
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::process::spawn_dummy_process;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(
+            parse_cpu_list("0-3,6,8-9\n").unwrap(),
+            vec![0, 1, 2, 3, 6, 8, 9]
+        );
+        assert_eq!(parse_cpu_list("0\n").unwrap(), vec![0]);
+        assert!(parse_cpu_list("3-1")
+            .unwrap_err()
+            .contains("Invalid CPU list (3-1)"));
+        assert!(parse_cpu_list("0-x").is_err());
+    }
+
+    #[test]
+    fn test_validate_cpu_set() {
+        let online = online_cpus().unwrap();
+        assert_eq!(
+            validate_cpu_set(vec![online[0], online[0]]).unwrap(),
+            vec![online[0]]
+        );
+        assert!(validate_cpu_set(Vec::new())
+            .unwrap_err()
+            .contains("at least one CPU"));
+        assert!(validate_cpu_set(vec![online[0], 100_000])
+            .unwrap_err()
+            .contains("Invalid CPU (100000). Expected one of the online CPUs: "));
+    }
+
+    #[test]
+    fn test_cpu_affinity() {
+        let mut child = spawn_dummy_process();
+        let id = child.id().to_string();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        let original = rt.block_on(get_cpu_affinity(&id)).unwrap();
+        assert!(!original.is_empty());
+        // The child inherits this process' affinity
+        assert_eq!(original, get_affinity(std::process::id()).unwrap());
+
+        let cpu = original[0];
+        let changes = rt
+            .block_on(set_cpu_affinity(&id, vec![cpu], Some(true)))
+            .unwrap();
+        assert_eq!(
+            changes,
+            vec![SettingChange {
+                id: id.clone(),
+                before: original.clone(),
+                after: vec![cpu],
+            }]
+        );
+        assert_eq!(rt.block_on(get_cpu_affinity(&id)).unwrap(), vec![cpu]);
+
+        // Undo with the previous value
+        let changes = rt
+            .block_on(set_cpu_affinity(&id, original.clone(), None))
+            .unwrap();
+        assert_eq!(changes[0].after, original);
+
+        assert!(rt
+            .block_on(get_cpu_affinity("999999999"))
+            .unwrap_err()
+            .contains("Process with ID 999999999 not found."));
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
@@ -0,0 +1,279 @@
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::Disks;
+use tauri::{AppHandle, Manager, State};
+
+use crate::monitor::{
+    emit_payload, event_payload_version, monitor_period, MonitorKind, MonitorRegistry,
+};
+use crate::sampler::unix_time_ms;
+use crate::system::{disk_refresh_kind, get_disk_info, DiskInfo};
+
+// /proc/diskstats counts sectors of 512 bytes, whatever the device's real sector size
+#[cfg(target_os = "linux")]
+const SECTOR_SIZE: u64 = 512;
+
+// Struct to contain the I/O activity of a single block device or partition
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DiskIoInfo {
+    // Kernel name of the device (e.g., "nvme0n1", "sda1")
+    device: String,
+    // Averaged over the time since the previous sample. 0 on the first sample
+    read_bytes_per_sec: u64,
+    written_bytes_per_sec: u64,
+    // Completed read and write requests (IOPS)
+    reads_per_sec: u64,
+    writes_per_sec: u64,
+    // Share of the time the device had requests in flight
+    busy_percent: f32,
+    // Totals since boot
+    read_bytes: u64,
+    written_bytes: u64,
+}
+
+// Payload of `disk_update` events
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DiskUpdate {
+    // Disks mounted at the time of the update
+    disks: Vec<DiskInfo>,
+    // Sorted by device name. Devices that never did any I/O are left out
+    io: Vec<DiskIoInfo>,
+}
+
+// Counters read from one line of /proc/diskstats
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct DiskStat {
+    reads: u64,
+    read_sectors: u64,
+    writes: u64,
+    written_sectors: u64,
+    busy_ms: u64,
+}
+
+/// Parses the contents of /proc/diskstats
+///
+/// Returns the counters of each device, keyed by device name. Malformed lines are skipped
+#[cfg(target_os = "linux")]
+fn parse_diskstats(diskstats: &str) -> HashMap<String, DiskStat> {
+    diskstats
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            // Field N of the kernel documentation is at index N - 1
+            let field = |number: usize| fields.get(number - 1)?.parse::<u64>().ok();
+
+            Some((
+                fields.get(2)?.to_string(),
+                DiskStat {
+                    reads: field(4)?,
+                    read_sectors: field(6)?,
+                    writes: field(8)?,
+                    written_sectors: field(10)?,
+                    busy_ms: field(13)?,
+                },
+            ))
+        })
+        .collect()
+}
+
+// Reads block device counters, keeping the previous sample to compute rates
+#[cfg(target_os = "linux")]
+pub(crate) struct DiskStatsSampler {
+    previous: HashMap<String, DiskStat>,
+    previous_at: Option<Instant>,
+}
+
+#[cfg(target_os = "linux")]
+impl DiskStatsSampler {
+    pub(crate) fn new() -> Self {
+        DiskStatsSampler {
+            previous: HashMap::new(),
+            previous_at: None,
+        }
+    }
+
+    /// Reads the I/O counters of every block device
+    ///
+    /// Rates are averaged over the time since the previous sample
+    ///
+    /// Returns a vector of `DiskIoInfo` structs sorted by device name or a String error if
+    /// /proc/diskstats can't be read
+    pub(crate) fn sample(&mut self) -> Result<Vec<DiskIoInfo>, String> {
+        let diskstats = std::fs::read_to_string("/proc/diskstats")
+            .map_err(|e| format!("Failed to read disk statistics: {e}"))?;
+        let now = Instant::now();
+        let elapsed_secs = self
+            .previous_at
+            .map(|previous_at| now.duration_since(previous_at).as_secs_f64())
+            .filter(|elapsed_secs| *elapsed_secs > 0.0);
+
+        let current = parse_diskstats(&diskstats);
+        let mut io = current
+            .iter()
+            .filter(|(_, stat)| stat.reads > 0 || stat.writes > 0)
+            .map(|(device, stat)| {
+                let previous = self.previous.get(device);
+                let per_sec = |count: fn(&DiskStat) -> u64| match (elapsed_secs, previous) {
+                    (Some(elapsed_secs), Some(previous)) => {
+                        (count(stat).saturating_sub(count(previous)) as f64 / elapsed_secs) as u64
+                    }
+                    _ => 0,
+                };
+                let busy_percent = match (elapsed_secs, previous) {
+                    (Some(elapsed_secs), Some(previous)) => {
+                        let busy_ms = stat.busy_ms.saturating_sub(previous.busy_ms) as f64;
+                        (busy_ms / 10.0 / elapsed_secs).min(100.0) as f32
+                    }
+                    _ => 0.0,
+                };
+
+                DiskIoInfo {
+                    device: device.clone(),
+                    read_bytes_per_sec: per_sec(|stat| stat.read_sectors * SECTOR_SIZE),
+                    written_bytes_per_sec: per_sec(|stat| stat.written_sectors * SECTOR_SIZE),
+                    reads_per_sec: per_sec(|stat| stat.reads),
+                    writes_per_sec: per_sec(|stat| stat.writes),
+                    busy_percent,
+                    read_bytes: stat.read_sectors * SECTOR_SIZE,
+                    written_bytes: stat.written_sectors * SECTOR_SIZE,
+                }
+            })
+            .collect::<Vec<DiskIoInfo>>();
+        self.previous = current;
+        self.previous_at = Some(now);
+
+        io.sort_by(|a, b| a.device.cmp(&b.device));
+        Ok(io)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) struct DiskStatsSampler;
+
+#[cfg(not(target_os = "linux"))]
+impl DiskStatsSampler {
+    pub(crate) fn new() -> Self {
+        DiskStatsSampler
+    }
+
+    pub(crate) fn sample(&mut self) -> Result<Vec<DiskIoInfo>, String> {
+        Err("Disk I/O statistics are only supported on Linux".to_owned())
+    }
+}
+
+/// Regularly updates the frontend on mounted disks and their I/O throughput
+///
+/// `app` is used to emit `disk_update` events carrying a `DiskUpdate`. The mount list is re-read on
+/// every update, so disks that are mounted or unmounted show up in the next event
+///
+/// `interval_ms`, `adaptive` and `payload_version` work like they do for `monitor_sys_info`
+///
+/// Starting a new disk monitor replaces the running one
+///
+/// Returns the ID of the new monitor or a String error if the interval or payload version is
+/// invalid
+#[tauri::command]
+pub async fn monitor_disks(
+    app: AppHandle,
+    interval_ms: Option<u64>,
+    adaptive: Option<bool>,
+    payload_version: Option<u32>,
+) -> Result<u64, String> {
+    let period = monitor_period(interval_ms)?;
+    let version = event_payload_version(payload_version)?;
+
+    let tick_app = app.clone();
+    let mut disks = Disks::new_with_refreshed_list_specifics(disk_refresh_kind());
+    let mut sampler = DiskStatsSampler::new();
+    let tick = move || {
+        // Picks up new mounts and drops unmounted disks
+        disks.refresh_specifics(true, disk_refresh_kind());
+        let disk_update = DiskUpdate {
+            disks: get_disk_info(&disks),
+            io: sampler.sample().unwrap_or_default(),
+        };
+
+        // Emit the event globally and handle potential error
+        if let Err(err) = emit_payload(
+            &tick_app,
+            "disk_update",
+            version,
+            unix_time_ms(),
+            disk_update,
+        ) {
+            eprintln!("Failed to emit disk_update event. Error: {err}");
+        };
+    };
+
+    let registry = app.state::<Mutex<MonitorRegistry>>();
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    Ok(registry_guard.start(MonitorKind::Disks, period, adaptive.unwrap_or(false), tick))
+}
+
+/// Stops the running disk monitor, if any
+///
+/// `registry` is a reference to the MonitorRegistry injected by Tauri
+///
+/// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
+#[tauri::command]
+pub fn stop_monitoring_disks(registry: State<'_, Mutex<MonitorRegistry>>) -> Result<(), String> {
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    registry_guard.stop_kind(MonitorKind::Disks);
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diskstats() {
+        let diskstats = "   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n \
+                         254       0 vda 8306 5536 2786242 16305 29472 55286 17364736 38666 0 17776 58154 8883 0 11632496 3030 3554 152\n \
+                         254       1 truncated 1 2\n";
+        let stats = parse_diskstats(diskstats);
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats["vda"],
+            DiskStat {
+                reads: 8306,
+                read_sectors: 2786242,
+                writes: 29472,
+                written_sectors: 17364736,
+                busy_ms: 17776,
+            }
+        );
+        assert_eq!(stats["loop0"].reads, 0);
+    }
+
+    #[test]
+    fn test_disk_stats_sampler() {
+        let mut sampler = DiskStatsSampler::new();
+        let first = sampler.sample().unwrap();
+        assert!(first.windows(2).all(|pair| pair[0].device < pair[1].device));
+        assert!(first
+            .iter()
+            .all(|io| io.read_bytes_per_sec == 0 && io.busy_percent == 0.0));
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let second = sampler.sample().unwrap();
+        for io in &second {
+            assert!((0.0..=100.0).contains(&io.busy_percent));
+            if let Some(previous) = first.iter().find(|previous| previous.device == io.device) {
+                assert!(io.read_bytes >= previous.read_bytes);
+                assert!(io.written_bytes >= previous.written_bytes);
+            }
+        }
+    }
+}
@@ -0,0 +1,751 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::watch;
+
+use crate::process::ProcessInfo;
+use crate::sampler::Snapshot;
+
+// One-second samples for 10 minutes and one-minute samples for 24 hours
+const DEFAULT_HISTORY_TIERS: [HistoryTier; 2] = [
+    HistoryTier {
+        resolution_ms: 1000,
+        retention_ms: 10 * 60 * 1000,
+    },
+    HistoryTier {
+        resolution_ms: 60 * 1000,
+        retention_ms: 24 * 60 * 60 * 1000,
+    },
+];
+
+// Longest retention a tier may have, which also keeps timestamp arithmetic far from overflowing
+const MAX_HISTORY_RETENTION_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+// Most memory the per-process series may take once their rings are full. With the default tiers a
+// process takes about 160 KB, so about 200 processes are tracked
+const PROCESS_HISTORY_BUDGET_BYTES: usize = 32 * 1024 * 1024;
+// How long the history of a process that exited is kept, unless the longest retention is shorter
+const EXITED_PROCESS_RETENTION_MS: u64 = 60 * 1000;
+
+// Value tracked over time, either system-wide or for a single process
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    CpuUsage,
+    // Memory used in bytes
+    MemoryUsed,
+    // Network throughput in bytes per second, either system-wide (excluding loopback) or for a
+    // single interface
+    NetworkReceived,
+    NetworkTransmitted,
+}
+
+// Struct to contain how finely and for how long a tier keeps samples
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct HistoryTier {
+    resolution_ms: u64,
+    retention_ms: u64,
+}
+
+impl HistoryTier {
+    // Enough full buckets to span the retention, plus the one being filled
+    fn capacity(&self) -> usize {
+        self.retention_ms.div_ceil(self.resolution_ms) as usize + 1
+    }
+}
+
+// Struct to contain the time range of a history query. An open end means "up to now"
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct TimeRange {
+    pub(crate) start_ms: u64,
+    pub(crate) end_ms: Option<u64>,
+}
+
+// Struct to contain the samples that fell within one bucket of a tier
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct HistoryPoint {
+    // Unix time in milliseconds at which the bucket starts
+    timestamp_ms: u64,
+    avg: f64,
+    min: f64,
+    max: f64,
+    samples: u32,
+}
+
+impl HistoryPoint {
+    fn new(timestamp_ms: u64, value: f64) -> Self {
+        HistoryPoint {
+            timestamp_ms,
+            avg: value,
+            min: value,
+            max: value,
+            samples: 1,
+        }
+    }
+
+    /// Folds another point into this one, weighting the average by sample count
+    fn merge(&mut self, other: &HistoryPoint) {
+        let samples = self.samples + other.samples;
+        self.avg = (self.avg * f64::from(self.samples) + other.avg * f64::from(other.samples))
+            / f64::from(samples);
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.samples = samples;
+    }
+}
+
+/// Rounds a timestamp down to the start of its bucket
+fn bucket_start(timestamp_ms: u64, resolution_ms: u64) -> u64 {
+    timestamp_ms - timestamp_ms % resolution_ms
+}
+
+// Bounded ring of buckets for a single tier. The last bucket is the one still being filled
+struct TierRing {
+    tier: HistoryTier,
+    buckets: VecDeque<HistoryPoint>,
+}
+
+impl TierRing {
+    fn new(tier: HistoryTier) -> Self {
+        TierRing {
+            tier,
+            buckets: VecDeque::new(),
+        }
+    }
+
+    fn record(&mut self, timestamp_ms: u64, value: f64) {
+        let start = bucket_start(timestamp_ms, self.tier.resolution_ms);
+        let point = HistoryPoint::new(start, value);
+
+        match self.buckets.back_mut() {
+            Some(last) if last.timestamp_ms == start => last.merge(&point),
+            // Ignore samples that arrive out of order
+            Some(last) if last.timestamp_ms > start => {}
+            _ => self.buckets.push_back(point),
+        }
+
+        // Drop buckets that fell out of the retention window or overflow the ring
+        let oldest = start.saturating_sub(self.tier.retention_ms);
+        while self
+            .buckets
+            .front()
+            .is_some_and(|first| first.timestamp_ms < oldest)
+            || self.buckets.len() > self.tier.capacity()
+        {
+            self.buckets.pop_front();
+        }
+    }
+}
+
+// All tiers of a single metric
+struct Series {
+    rings: Vec<TierRing>,
+    last_seen_ms: u64,
+    // Start time of the process a per-process series belongs to. A different start time under the
+    // same PID means the PID was reused, so the old history is discarded
+    start_time: Option<u64>,
+}
+
+// Bounded in-memory time-series store fed by every sampler snapshot
+pub struct HistoryStore {
+    tiers: Vec<HistoryTier>,
+    // Keyed by metric and subject: a process ID, an interface name for network metrics or `None`
+    // for system-wide series
+    series: HashMap<(Metric, Option<String>), Series>,
+    // Time of the most recent sample, used as "now" when choosing a tier
+    latest_ms: u64,
+}
+
+impl Default for HistoryStore {
+    fn default() -> Self {
+        HistoryStore {
+            tiers: DEFAULT_HISTORY_TIERS.to_vec(),
+            series: HashMap::new(),
+            latest_ms: 0,
+        }
+    }
+}
+
+impl HistoryStore {
+    /// Creates an empty store
+    ///
+    /// `tiers` must be ordered from finest to coarsest resolution
+    ///
+    /// Returns the `HistoryStore` or a String error if the tiers are invalid
+    pub(crate) fn new(tiers: Vec<HistoryTier>) -> Result<Self, String> {
+        if tiers.is_empty() {
+            return Err("At least one history tier is required".to_owned());
+        }
+        for tier in &tiers {
+            if tier.resolution_ms == 0 || tier.retention_ms < tier.resolution_ms {
+                return Err(format!(
+                    "Invalid history tier ({} ms every {} ms): retention must be at least one \
+                     resolution",
+                    tier.retention_ms, tier.resolution_ms
+                ));
+            }
+            if tier.retention_ms > MAX_HISTORY_RETENTION_MS {
+                return Err(format!(
+                    "Invalid history tier: retention of {} ms exceeds the maximum of \
+                     {MAX_HISTORY_RETENTION_MS} ms",
+                    tier.retention_ms
+                ));
+            }
+        }
+        if tiers
+            .windows(2)
+            .any(|pair| pair[1].resolution_ms <= pair[0].resolution_ms)
+        {
+            return Err(
+                "History tiers must be ordered from finest to coarsest resolution".to_owned(),
+            );
+        }
+
+        Ok(HistoryStore {
+            tiers,
+            ..Default::default()
+        })
+    }
+
+    /// Records a single sample of a metric
+    ///
+    /// `subject` is the process (or network interface) the sample belongs to, or `None` for
+    /// system-wide metrics
+    pub(crate) fn record(
+        &mut self,
+        metric: Metric,
+        subject: Option<&str>,
+        timestamp_ms: u64,
+        value: f64,
+    ) {
+        self.record_series(metric, subject, None, timestamp_ms, value);
+    }
+
+    /// Records a single sample of a per-process metric
+    ///
+    /// `start_time` tells apart processes that reuse the same `pid`. A new process starts a fresh
+    /// series instead of continuing the history of the one that exited
+    fn record_process(
+        &mut self,
+        metric: Metric,
+        pid: &str,
+        start_time: u64,
+        timestamp_ms: u64,
+        value: f64,
+    ) {
+        self.record_series(metric, Some(pid), Some(start_time), timestamp_ms, value);
+    }
+
+    /// Records a sample into the series of `metric` and `subject`, starting the series over if
+    /// `start_time` differs from the one it was created with
+    fn record_series(
+        &mut self,
+        metric: Metric,
+        subject: Option<&str>,
+        start_time: Option<u64>,
+        timestamp_ms: u64,
+        value: f64,
+    ) {
+        let tiers = &self.tiers;
+        let new_series = || Series {
+            rings: tiers.iter().copied().map(TierRing::new).collect(),
+            last_seen_ms: timestamp_ms,
+            start_time,
+        };
+        let series = self
+            .series
+            .entry((metric, subject.map(str::to_owned)))
+            .and_modify(|series| {
+                if series.start_time != start_time {
+                    *series = new_series();
+                }
+            })
+            .or_insert_with(new_series);
+
+        for ring in &mut series.rings {
+            ring.record(timestamp_ms, value);
+        }
+        series.last_seen_ms = series.last_seen_ms.max(timestamp_ms);
+        self.latest_ms = self.latest_ms.max(timestamp_ms);
+    }
+
+    /// Most processes whose history fits in `PROCESS_HISTORY_BUDGET_BYTES` with the current tiers
+    fn max_tracked_processes(&self) -> usize {
+        let series_bytes = self
+            .tiers
+            .iter()
+            .map(|tier| tier.capacity() * std::mem::size_of::<HistoryPoint>())
+            .sum::<usize>();
+        // Each process has a CPU and a memory series
+        PROCESS_HISTORY_BUDGET_BYTES / (2 * series_bytes)
+    }
+
+    /// Records every system and process metric of a snapshot
+    ///
+    /// Threads are skipped since they share their process' memory and CPU time. Once
+    /// `max_tracked_processes` processes are tracked, new processes are left out until a tracked
+    /// one exits and its series is pruned. Busier processes are admitted first
+    pub(crate) fn record_snapshot(&mut self, snapshot: &Snapshot) {
+        let timestamp_ms = snapshot.timestamp_ms;
+        let system = &snapshot.system;
+        self.record(
+            Metric::CpuUsage,
+            None,
+            timestamp_ms,
+            f64::from(system.cpu_usage_percent),
+        );
+        self.record(
+            Metric::MemoryUsed,
+            None,
+            timestamp_ms,
+            system.used_memory_bytes as f64,
+        );
+
+        let mut procs = snapshot
+            .processes
+            .iter()
+            .filter(|proc| !proc.is_thread)
+            .collect::<Vec<&ProcessInfo>>();
+        procs.sort_by(|a, b| {
+            b.cpu_usage_percent
+                .partial_cmp(&a.cpu_usage_percent)
+                .unwrap_or(Ordering::Equal)
+        });
+        let max_tracked = self.max_tracked_processes();
+        let mut tracked = self
+            .series
+            .iter()
+            .filter(|((metric, _), series)| {
+                *metric == Metric::CpuUsage && series.start_time.is_some()
+            })
+            .count();
+        for proc in procs {
+            // A reused PID takes over the series of the process that exited
+            if !self
+                .series
+                .contains_key(&(Metric::CpuUsage, Some(proc.id.clone())))
+            {
+                if tracked >= max_tracked {
+                    continue;
+                }
+                tracked += 1;
+            }
+            self.record_process(
+                Metric::CpuUsage,
+                &proc.id,
+                proc.start_time,
+                timestamp_ms,
+                f64::from(proc.cpu_usage_percent),
+            );
+            self.record_process(
+                Metric::MemoryUsed,
+                &proc.id,
+                proc.start_time,
+                timestamp_ms,
+                proc.memory_bytes as f64,
+            );
+        }
+
+        let mut received_bytes_per_sec = 0;
+        let mut transmitted_bytes_per_sec = 0;
+        for interface in &snapshot.networks {
+            self.record(
+                Metric::NetworkReceived,
+                Some(&interface.name),
+                timestamp_ms,
+                interface.received_bytes_per_sec as f64,
+            );
+            self.record(
+                Metric::NetworkTransmitted,
+                Some(&interface.name),
+                timestamp_ms,
+                interface.transmitted_bytes_per_sec as f64,
+            );
+            if !interface.loopback {
+                received_bytes_per_sec += interface.received_bytes_per_sec;
+                transmitted_bytes_per_sec += interface.transmitted_bytes_per_sec;
+            }
+        }
+        self.record(
+            Metric::NetworkReceived,
+            None,
+            timestamp_ms,
+            received_bytes_per_sec as f64,
+        );
+        self.record(
+            Metric::NetworkTransmitted,
+            None,
+            timestamp_ms,
+            transmitted_bytes_per_sec as f64,
+        );
+
+        self.prune(timestamp_ms);
+    }
+
+    /// Removes series of interfaces that haven't been updated within the longest retention, and of
+    /// processes that exited more than `EXITED_PROCESS_RETENTION_MS` ago
+    fn prune(&mut self, now_ms: u64) {
+        let retention_ms = self
+            .tiers
+            .iter()
+            .map(|tier| tier.retention_ms)
+            .max()
+            .unwrap_or_default();
+        let exited_retention_ms = retention_ms.min(EXITED_PROCESS_RETENTION_MS);
+        self.series.retain(|_, series| {
+            let retention_ms = if series.start_time.is_some() {
+                exited_retention_ms
+            } else {
+                retention_ms
+            };
+            series.last_seen_ms.saturating_add(retention_ms) >= now_ms
+        });
+    }
+
+    /// Gets the history of a metric
+    ///
+    /// Uses the finest tier that still covers the start of `range`. `resolution_ms` is rounded up
+    /// to that tier's resolution, and coarser resolutions are produced by merging its buckets
+    ///
+    /// Returns the points within `range`, oldest first, or a String error if the range is invalid
+    pub(crate) fn query(
+        &self,
+        metric: Metric,
+        subject: Option<&str>,
+        range: TimeRange,
+        resolution_ms: Option<u64>,
+    ) -> Result<Vec<HistoryPoint>, String> {
+        let end_ms = range.end_ms.unwrap_or(u64::MAX);
+        if range.start_ms > end_ms {
+            return Err(format!(
+                "Invalid time range: start ({}) is after end ({end_ms})",
+                range.start_ms
+            ));
+        }
+
+        let Some(series) = self.series.get(&(metric, subject.map(str::to_owned))) else {
+            return Ok(Vec::new());
+        };
+        let Some(ring) = series
+            .rings
+            .iter()
+            .find(|ring| self.latest_ms.saturating_sub(ring.tier.retention_ms) <= range.start_ms)
+            .or(series.rings.last())
+        else {
+            return Ok(Vec::new());
+        };
+
+        let resolution_ms = resolution_ms
+            .unwrap_or_default()
+            .max(ring.tier.resolution_ms);
+        let mut points: Vec<HistoryPoint> = Vec::new();
+        for bucket in &ring.buckets {
+            if bucket.timestamp_ms < bucket_start(range.start_ms, ring.tier.resolution_ms)
+                || bucket.timestamp_ms > end_ms
+            {
+                continue;
+            }
+
+            let start = bucket_start(bucket.timestamp_ms, resolution_ms);
+            match points.last_mut() {
+                Some(last) if last.timestamp_ms == start => last.merge(bucket),
+                _ => points.push(HistoryPoint {
+                    timestamp_ms: start,
+                    ..*bucket
+                }),
+            }
+        }
+        Ok(points)
+    }
+}
+
+/// Feeds every snapshot taken by the sampler into the managed HistoryStore
+///
+/// `snapshots` is a receiver from `SamplerState::subscribe`. Runs until the sampler stops
+pub(crate) async fn record_history(app: AppHandle, mut snapshots: watch::Receiver<Arc<Snapshot>>) {
+    loop {
+        let snapshot = snapshots.borrow_and_update().clone();
+        if let Ok(mut history_guard) = app.state::<Mutex<HistoryStore>>().lock() {
+            history_guard.record_snapshot(&snapshot);
+        }
+
+        if snapshots.changed().await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Gets the recorded history of a system or process metric
+///
+/// `history` is a reference to the HistoryStore injected by Tauri, `pid` selects a process instead
+/// of the whole system, `interface` selects a network interface for network metrics and
+/// `resolution_ms` optionally downsamples the result
+///
+/// Returns a vector of `HistoryPoint` structs if successful and a String error if unsuccessful
+#[tauri::command]
+pub fn query_history(
+    history: State<'_, Mutex<HistoryStore>>,
+    metric: Metric,
+    pid: Option<String>,
+    interface: Option<String>,
+    range: TimeRange,
+    resolution_ms: Option<u64>,
+) -> Result<Vec<HistoryPoint>, String> {
+    if pid.is_some() && interface.is_some() {
+        return Err("Only one of pid and interface may be given".to_owned());
+    }
+    let Ok(history_guard) = history.lock() else {
+        return Err("Failed to acquire lock on history store".to_owned());
+    };
+    history_guard.query(metric, pid.or(interface).as_deref(), range, resolution_ms)
+}
+
+/// Replaces the retention and downsampling tiers of the history store
+///
+/// `history` is a reference to the HistoryStore injected by Tauri. Recorded history is discarded
+///
+/// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
+#[tauri::command]
+pub fn configure_history(
+    history: State<'_, Mutex<HistoryStore>>,
+    tiers: Vec<HistoryTier>,
+) -> Result<(), String> {
+    let store = HistoryStore::new(tiers)?;
+    let Ok(mut history_guard) = history.lock() else {
+        return Err("Failed to acquire lock on history store".to_owned());
+    };
+    *history_guard = store;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    use crate::sampler::Sampler;
+
+    fn tier(resolution_ms: u64, retention_ms: u64) -> HistoryTier {
+        HistoryTier {
+            resolution_ms,
+            retention_ms,
+        }
+    }
+
+    fn recorded_pids(store: &HistoryStore) -> HashSet<String> {
+        store
+            .series
+            .keys()
+            .filter(|(metric, _)| matches!(metric, Metric::CpuUsage | Metric::MemoryUsed))
+            .filter_map(|(_, pid)| pid.clone())
+            .collect()
+    }
+
+    fn range(start_ms: u64, end_ms: Option<u64>) -> TimeRange {
+        TimeRange { start_ms, end_ms }
+    }
+
+    #[test]
+    fn test_history_store_validation() {
+        assert!(HistoryStore::new(Vec::new()).is_err());
+        assert!(HistoryStore::new(vec![tier(0, 10)]).is_err());
+        assert!(HistoryStore::new(vec![tier(10, 5)]).is_err());
+        assert!(HistoryStore::new(vec![tier(10, 100), tier(10, 1000)]).is_err());
+        assert!(HistoryStore::new(vec![tier(10, u64::MAX)])
+            .err()
+            .unwrap()
+            .contains("exceeds the maximum"));
+        assert!(HistoryStore::new(vec![tier(10, 100), tier(100, 1000)]).is_ok());
+    }
+
+    #[test]
+    fn test_history_retention_and_downsampling() {
+        let mut store = HistoryStore::new(vec![tier(10, 100), tier(100, 1000)]).unwrap();
+        for timestamp_ms in (0..=1000).step_by(5) {
+            store.record(Metric::CpuUsage, None, timestamp_ms, timestamp_ms as f64);
+        }
+
+        // Recent ranges come from the fine tier, which only keeps the last 100ms
+        let points = store
+            .query(Metric::CpuUsage, None, range(950, None), None)
+            .unwrap();
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[0].timestamp_ms, 950);
+        assert_eq!(points[0].samples, 2);
+        assert_eq!(points[0].avg, 952.5);
+        assert_eq!(points[0].min, 950.0);
+        assert_eq!(points[0].max, 955.0);
+
+        // Older ranges fall back to the coarse tier
+        let points = store
+            .query(Metric::CpuUsage, None, range(0, Some(450)), None)
+            .unwrap();
+        let timestamps = points
+            .iter()
+            .map(|point| point.timestamp_ms)
+            .collect::<Vec<u64>>();
+        assert_eq!(timestamps, vec![0, 100, 200, 300, 400]);
+        assert_eq!(points[1].samples, 20);
+        assert_eq!(points[1].avg, 147.5);
+
+        // A coarser resolution merges the tier's buckets
+        let points = store
+            .query(Metric::CpuUsage, None, range(0, Some(999)), Some(500))
+            .unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].samples, 100);
+        assert_eq!(points[0].min, 0.0);
+        assert_eq!(points[0].max, 495.0);
+
+        // The ring never grows past its retention
+        let series = &store.series[&(Metric::CpuUsage, None)];
+        assert!(series.rings[0].buckets.len() <= 11);
+        assert!(series.rings[1].buckets.len() <= 11);
+
+        assert!(store
+            .query(Metric::CpuUsage, None, range(10, Some(5)), None)
+            .is_err());
+        assert!(store
+            .query(Metric::MemoryUsed, Some("1"), range(0, None), None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_history_prunes_exited_processes() {
+        let mut store = HistoryStore::new(vec![tier(10, 100)]).unwrap();
+        let mut snapshot = Sampler::new().sample();
+        let own_id = std::process::id().to_string();
+        snapshot.processes.retain(|proc| proc.id == own_id);
+
+        snapshot.timestamp_ms = 1000;
+        store.record_snapshot(&snapshot);
+        assert!(recorded_pids(&store).contains(&own_id));
+        let points = store
+            .query(Metric::MemoryUsed, Some(&own_id), range(0, None), None)
+            .unwrap();
+        assert_eq!(points.len(), 1);
+        assert!(points[0].avg > 0.0);
+
+        // The process exits and its history ages out
+        snapshot.processes.clear();
+        snapshot.timestamp_ms = 1050;
+        store.record_snapshot(&snapshot);
+        assert!(recorded_pids(&store).contains(&own_id));
+        snapshot.timestamp_ms = 1200;
+        store.record_snapshot(&snapshot);
+        assert!(recorded_pids(&store).is_empty());
+        // System-wide history is never pruned, only trimmed to the retention
+        assert_eq!(
+            store
+                .query(Metric::CpuUsage, None, range(0, None), None)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_history_skips_threads_and_is_bounded() {
+        let mut store = HistoryStore::default();
+        let max_tracked = store.max_tracked_processes();
+        assert!((100..1000).contains(&max_tracked));
+
+        let mut snapshot = Sampler::new().sample();
+        let template = snapshot.processes[0].clone();
+        snapshot.processes = (0..max_tracked + 10)
+            .map(|id| ProcessInfo {
+                id: id.to_string(),
+                cpu_usage_percent: id as f32,
+                // Threads never take a slot
+                is_thread: id % 2 == 1,
+                ..template.clone()
+            })
+            .collect();
+        snapshot.timestamp_ms = 1000;
+        store.record_snapshot(&snapshot);
+        let pids = recorded_pids(&store);
+        assert_eq!(pids.len(), (max_tracked + 10).div_ceil(2));
+        assert!(pids
+            .iter()
+            .all(|pid| pid.parse::<usize>().unwrap() % 2 == 0));
+
+        // Once every slot is taken, the remaining newcomers are left out
+        snapshot.processes = (0..max_tracked + 1)
+            .map(|id| ProcessInfo {
+                id: id.to_string(),
+                cpu_usage_percent: id as f32,
+                is_thread: false,
+                ..template.clone()
+            })
+            .collect();
+        snapshot.timestamp_ms = 2000;
+        store.record_snapshot(&snapshot);
+        assert_eq!(recorded_pids(&store).len(), max_tracked);
+
+        // Exited processes are dropped well before the longest retention
+        snapshot.processes.clear();
+        snapshot.timestamp_ms = 2000 + EXITED_PROCESS_RETENTION_MS + 1;
+        store.record_snapshot(&snapshot);
+        assert!(recorded_pids(&store).is_empty());
+    }
+
+    #[test]
+    fn test_history_records_network() {
+        let mut store = HistoryStore::new(vec![tier(10, 100)]).unwrap();
+        let mut snapshot = Sampler::new().sample();
+        snapshot.processes.clear();
+        snapshot.timestamp_ms = 1000;
+        store.record_snapshot(&snapshot);
+
+        for metric in [Metric::NetworkReceived, Metric::NetworkTransmitted] {
+            assert_eq!(
+                store
+                    .query(metric, None, range(0, None), None)
+                    .unwrap()
+                    .len(),
+                1
+            );
+            for interface in &snapshot.networks {
+                assert_eq!(
+                    store
+                        .query(metric, Some(&interface.name), range(0, None), None)
+                        .unwrap()
+                        .len(),
+                    1
+                );
+            }
+        }
+        // Interface series aren't mistaken for processes
+        assert!(recorded_pids(&store).is_empty());
+    }
+
+    #[test]
+    fn test_history_restarts_on_reused_pid() {
+        let mut store = HistoryStore::new(vec![tier(1000, 10_000)]).unwrap();
+        let mut snapshot = Sampler::new().sample();
+        let own_id = std::process::id().to_string();
+        snapshot.processes.retain(|proc| proc.id == own_id);
+
+        snapshot.timestamp_ms = 1000;
+        store.record_snapshot(&snapshot);
+        snapshot.timestamp_ms = 2000;
+        store.record_snapshot(&snapshot);
+        let query = |store: &HistoryStore| {
+            store
+                .query(Metric::MemoryUsed, Some(&own_id), range(0, None), None)
+                .unwrap()
+        };
+        assert_eq!(query(&store).len(), 2);
+
+        // Another process gets the same PID
+        snapshot.processes[0].start_time += 1;
+        snapshot.timestamp_ms = 3000;
+        store.record_snapshot(&snapshot);
+        let points = query(&store);
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].timestamp_ms, 3000);
+    }
+}
@@ -1,14 +1,27 @@
 use std::sync::Mutex;
-use tauri::Manager;
+use tauri::{Manager, WindowEvent};
 
+mod affinity;
+mod disks;
+mod history;
+mod lifecycle;
+mod limits;
+mod memory_map;
+mod monitor;
+mod network;
+mod open_files;
+mod priority;
 mod process;
+mod process_delta;
+mod process_details;
+mod process_query;
+mod process_tree;
+mod recorder;
+mod sampler;
+mod setting;
+mod sockets;
 mod system;
-
-#[derive(Default)]
-struct MonitorUpdateState {
-    stop_process_updates: bool,
-    stop_system_updates: bool,
-}
+mod threads;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -19,14 +32,88 @@ pub fn run() {
             system::monitor_sys_info,
             system::stop_monitoring_system,
             system::get_all_disks,
+            disks::monitor_disks,
+            disks::stop_monitoring_disks,
+            history::query_history,
+            history::configure_history,
+            lifecycle::monitor_process_lifecycle,
+            lifecycle::stop_monitoring_process_lifecycle,
+            monitor::stop_monitor,
+            monitor::pause_monitor,
+            monitor::resume_monitor,
+            monitor::set_monitor_interval,
+            monitor::get_monitor_status,
+            network::monitor_network,
+            network::stop_monitoring_network,
+            memory_map::get_memory_map,
+            open_files::list_open_files,
             process::monitor_processes,
             process::stop_monitoring_processes,
             process::try_kill_process_by_id,
             process::force_kill_process_by_id,
+            process::suspend_process,
+            process::resume_process,
+            process::restart_process,
+            process::kill_process_tree,
+            priority::set_process_priority,
+            priority::set_io_priority,
+            affinity::get_cpu_affinity,
+            affinity::set_cpu_affinity,
+            limits::get_resource_limits,
+            limits::set_resource_limit,
+            process_delta::resync,
+            process_details::get_process_details,
+            process_query::update_process_query,
+            process_tree::get_process_tree,
+            sockets::list_process_sockets,
+            sockets::find_process_by_port,
+            threads::list_threads,
+            threads::monitor_threads,
+            threads::stop_monitoring_threads,
+            recorder::start_recording,
+            recorder::stop_recording,
+            recorder::list_recording_sessions,
+            recorder::query_recorded_system,
+            recorder::query_recorded_processes,
+            recorder::prune_recordings,
         ])
+        .on_window_event(|window, event| {
+            // Let adaptive monitors slow down while the window can't be seen
+            let background = match event {
+                WindowEvent::Focused(true) => false,
+                WindowEvent::Focused(false) | WindowEvent::Resized(_) => {
+                    window.is_minimized().unwrap_or(false) || !window.is_visible().unwrap_or(true)
+                }
+                _ => return,
+            };
+            if let Ok(mut registry_guard) = window.state::<Mutex<monitor::MonitorRegistry>>().lock()
+            {
+                registry_guard.set_background(background);
+            }
+        })
         .setup(|app| {
-            // Set default MonitorUpdateState
-            app.manage(Mutex::new(MonitorUpdateState::default()));
+            // Start the shared sampler that every monitor and command reads from
+            let sampler = sampler::SamplerState::start(monitor::DEFAULT_MONITOR_INTERVAL);
+            // Set empty MonitorRegistry that retunes the sampler as monitors come and go
+            app.manage(Mutex::new(monitor::MonitorRegistry::new(
+                sampler.period_sender(),
+            )));
+            // Record every snapshot into the bounded history store
+            app.manage(Mutex::new(history::HistoryStore::default()));
+            let history_app_handle = app.handle().clone();
+            let snapshots = sampler.subscribe();
+            tauri::async_runtime::spawn(history::record_history(history_app_handle, snapshots));
+            app.manage(sampler);
+            // Collects exit statuses of processes spawned by the app
+            app.manage(lifecycle::ChildExits::default());
+            // Recording is off until the frontend starts it
+            app.manage(Mutex::new(recorder::RecorderState::default()));
+            // Set default ProcessQuery (no filter, sorted by CPU usage)
+            app.manage(Mutex::new(process_query::ProcessQuery::default()));
+            // Optional process list column values, reused for a few seconds
+            app.manage(Mutex::new(process_query::ProcessColumnCache::default()));
+            // Remembers the last list sent by a delta-encoded process monitor
+            app.manage(Mutex::new(process_delta::ProcessDeltaEncoder::default()));
             // // Create tokio runtime
             // let rt = tokio::runtime::Runtime::new().unwrap();
             // // Gett app handle clones
@@ -0,0 +1,464 @@
+use std::collections::HashMap;
+use std::process::{Child, ExitStatus};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Manager, State};
+
+use crate::monitor::{
+    emit_payload, event_payload_version, monitor_period, MonitorKind, MonitorRegistry,
+};
+use crate::process::ProcessInfo;
+use crate::sampler::SamplerState;
+
+// Most records sent in a single `process_lifecycle` event. The rest are only counted
+const MAX_RECORDS_PER_BATCH: usize = 200;
+// How long an exit status is kept for a lifecycle monitor to pick up. Statuses are dropped after
+// that even if no monitor ran, so they can't pile up
+const EXIT_RETENTION: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum LifecycleEvent {
+    Started,
+    Exited,
+}
+
+// Struct to contain how a process exited. Only known for processes this app spawned
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ExitInfo {
+    code: Option<i32>,
+    // Signal that terminated the process (Unix only)
+    signal: Option<i32>,
+}
+
+impl From<ExitStatus> for ExitInfo {
+    fn from(status: ExitStatus) -> Self {
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+        #[cfg(not(unix))]
+        let signal = None;
+
+        ExitInfo {
+            code: status.code(),
+            signal,
+        }
+    }
+}
+
+// Struct to contain a single process start or exit
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct LifecycleRecord {
+    event: LifecycleEvent,
+    id: String,
+    parent_id: Option<String>,
+    name: String,
+    command_line: String,
+    // Unix time in seconds at which the process started
+    start_time: u64,
+    // How long the process had been running when the event was detected
+    lifetime_secs: u64,
+    exit: Option<ExitInfo>,
+}
+
+// Payload of `process_lifecycle` events
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct LifecycleBatch {
+    records: Vec<LifecycleRecord>,
+    // Records left out because the batch was full
+    dropped_started: usize,
+    dropped_exited: usize,
+}
+
+impl LifecycleBatch {
+    fn is_empty(&self) -> bool {
+        self.records.is_empty() && self.dropped_started == 0 && self.dropped_exited == 0
+    }
+}
+
+// Managed state collecting the exit status of child processes spawned by the app. Statuses are
+// keyed by PID and start time so that a reused PID doesn't pick up another process' exit
+#[derive(Clone, Default)]
+pub struct ChildExits {
+    statuses: Arc<Mutex<HashMap<(u32, u64), RecordedExit>>>,
+}
+
+// Exit status of a child along with when it was recorded
+struct RecordedExit {
+    recorded_at: Instant,
+    exit: ExitInfo,
+}
+
+/// Reads the start time of a process the way the sampler reports it
+///
+/// Returns the Unix time in seconds or `None` if the process can't be found
+fn read_start_time(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        false,
+        ProcessRefreshKind::nothing(),
+    );
+    sys.process(pid).map(|process| process.start_time())
+}
+
+impl ChildExits {
+    /// Reaps a spawned child on a background thread and records how it exited
+    ///
+    /// Also keeps the child from lingering as a zombie
+    pub(crate) fn reap(&self, mut child: Child) {
+        let statuses = self.statuses.clone();
+        thread::spawn(move || {
+            let pid = child.id();
+            // The child can't be reaped before `wait`, so its start time is still readable
+            let start_time = read_start_time(pid);
+            let Ok(status) = child.wait() else {
+                return;
+            };
+            let (Some(start_time), Ok(mut statuses_guard)) = (start_time, statuses.lock()) else {
+                return;
+            };
+            let now = Instant::now();
+            statuses_guard
+                .retain(|_, recorded| now.duration_since(recorded.recorded_at) < EXIT_RETENTION);
+            statuses_guard.insert(
+                (pid, start_time),
+                RecordedExit {
+                    recorded_at: now,
+                    exit: status.into(),
+                },
+            );
+        });
+    }
+
+    /// Removes and returns the recorded exit status of a child, if any
+    ///
+    /// `start_time` must match the one of the process that exited
+    pub(crate) fn take(&self, pid: &str, start_time: u64) -> Option<ExitInfo> {
+        let pid = pid.parse::<u32>().ok()?;
+        let recorded = self.statuses.lock().ok()?.remove(&(pid, start_time))?;
+        Some(recorded.exit)
+    }
+}
+
+/// Builds a record for a process
+fn lifecycle_record(
+    event: LifecycleEvent,
+    proc: &ProcessInfo,
+    lifetime_secs: u64,
+    exit: Option<ExitInfo>,
+) -> LifecycleRecord {
+    LifecycleRecord {
+        event,
+        id: proc.id.clone(),
+        parent_id: proc.parent_id.clone(),
+        name: proc.name.clone(),
+        command_line: proc.command_line.clone(),
+        start_time: proc.start_time,
+        lifetime_secs,
+        exit,
+    }
+}
+
+// Detects processes that started or exited between two observed process lists
+#[derive(Default)]
+pub(crate) struct LifecycleTracker {
+    // `None` until the first list has been observed
+    known: Option<HashMap<String, ProcessInfo>>,
+}
+
+impl LifecycleTracker {
+    /// Compares a process list to the previously observed one
+    ///
+    /// `procs` comes from a snapshot taken at `timestamp_ms`. Threads are ignored, and a PID that
+    /// was reused is reported as an exit followed by a start. The first list only sets the baseline
+    ///
+    /// Returns a `LifecycleBatch` with exits first, capped at `MAX_RECORDS_PER_BATCH` records
+    pub(crate) fn observe(
+        &mut self,
+        procs: &[ProcessInfo],
+        timestamp_ms: u64,
+        exits: &ChildExits,
+    ) -> LifecycleBatch {
+        let current = procs
+            .iter()
+            .filter(|proc| !proc.is_thread)
+            .map(|proc| (proc.id.clone(), proc.clone()))
+            .collect::<HashMap<String, ProcessInfo>>();
+        let Some(known) = self.known.replace(current) else {
+            return LifecycleBatch::default();
+        };
+        let current = self.known.as_ref().unwrap();
+        let now_secs = timestamp_ms / 1000;
+
+        let is_same = |old: &ProcessInfo, new: &ProcessInfo| old.start_time == new.start_time;
+        let mut exited = known
+            .values()
+            .filter(|old| current.get(&old.id).is_none_or(|new| !is_same(old, new)))
+            .map(|old| {
+                lifecycle_record(
+                    LifecycleEvent::Exited,
+                    old,
+                    now_secs.saturating_sub(old.start_time),
+                    exits.take(&old.id, old.start_time),
+                )
+            })
+            .collect::<Vec<LifecycleRecord>>();
+        let mut started = current
+            .values()
+            .filter(|new| known.get(&new.id).is_none_or(|old| !is_same(old, new)))
+            .map(|new| lifecycle_record(LifecycleEvent::Started, new, new.run_time_secs, None))
+            .collect::<Vec<LifecycleRecord>>();
+        exited.sort_by_key(|record| record.start_time);
+        started.sort_by_key(|record| record.start_time);
+
+        let dropped_exited = exited.len().saturating_sub(MAX_RECORDS_PER_BATCH);
+        exited.truncate(MAX_RECORDS_PER_BATCH);
+        let dropped_started = started
+            .len()
+            .saturating_sub(MAX_RECORDS_PER_BATCH - exited.len());
+        started.truncate(MAX_RECORDS_PER_BATCH - exited.len());
+
+        exited.append(&mut started);
+        LifecycleBatch {
+            records: exited,
+            dropped_started,
+            dropped_exited,
+        }
+    }
+}
+
+/// Regularly notifies the frontend of processes that started or exited
+///
+/// `app` is used to emit `process_lifecycle` events. An event is only emitted when something
+/// changed, and holds at most 200 records so that a burst of processes can't flood the webview
+///
+/// `interval_ms`, `adaptive` and `payload_version` work like they do for `monitor_processes`
+///
+/// Starting a new lifecycle monitor replaces the running one
+///
+/// Returns the ID of the new monitor or a String error if the interval or payload version is
+/// invalid
+#[tauri::command]
+pub async fn monitor_process_lifecycle(
+    app: AppHandle,
+    interval_ms: Option<u64>,
+    adaptive: Option<bool>,
+    payload_version: Option<u32>,
+) -> Result<u64, String> {
+    let period = monitor_period(interval_ms)?;
+    let version = event_payload_version(payload_version)?;
+
+    let tick_app = app.clone();
+    let mut tracker = LifecycleTracker::default();
+    let tick = move || {
+        let snapshot = tick_app.state::<SamplerState>().latest();
+        let exits = tick_app.state::<ChildExits>();
+        let batch = tracker.observe(&snapshot.processes, snapshot.timestamp_ms, &exits);
+        if batch.is_empty() {
+            return;
+        }
+
+        // Emit the event globally and handle potential error
+        if let Err(err) = emit_payload(
+            &tick_app,
+            "process_lifecycle",
+            version,
+            snapshot.timestamp_ms,
+            batch,
+        ) {
+            eprintln!("Failed to emit process_lifecycle event. Error: {err}");
+        };
+    };
+
+    let registry = app.state::<Mutex<MonitorRegistry>>();
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    Ok(registry_guard.start(
+        MonitorKind::Lifecycle,
+        period,
+        adaptive.unwrap_or(false),
+        tick,
+    ))
+}
+
+/// Stops the running lifecycle monitor, if any
+///
+/// `registry` is a reference to the MonitorRegistry injected by Tauri
+///
+/// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
+#[tauri::command]
+pub fn stop_monitoring_process_lifecycle(
+    registry: State<'_, Mutex<MonitorRegistry>>,
+) -> Result<(), String> {
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    registry_guard.stop_kind(MonitorKind::Lifecycle);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampler::Sampler;
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+
+    fn mock_process(id: u32, start_time: u64) -> ProcessInfo {
+        ProcessInfo {
+            id: id.to_string(),
+            parent_id: Some("1".to_owned()),
+            name: format!("proc-{id}"),
+            owner: String::new(),
+            running_time_formatted: String::new(),
+            memory_used: String::new(),
+            status: String::new(),
+            cpu_usage_percent: 0.0,
+            memory_bytes: 0,
+            run_time_secs: 0,
+            start_time,
+            disk_read_bytes_per_sec: 0,
+            disk_written_bytes_per_sec: 0,
+            disk_read_bytes: 0,
+            disk_written_bytes: 0,
+            pss_bytes: None,
+            uss_bytes: None,
+            socket_count: None,
+            command_line: format!("proc-{id} --flag"),
+            is_thread: false,
+        }
+    }
+
+    fn events(batch: &LifecycleBatch) -> Vec<(LifecycleEvent, &str)> {
+        batch
+            .records
+            .iter()
+            .map(|record| (record.event, record.id.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_observe() {
+        let exits = ChildExits::default();
+        let mut tracker = LifecycleTracker::default();
+
+        let mut thread = mock_process(4, 0);
+        thread.is_thread = true;
+        let first = vec![mock_process(1, 0), mock_process(2, 10), thread];
+        assert!(tracker.observe(&first, 20_000, &exits).is_empty());
+
+        // 2 exits, 3 starts and 1's PID is reused
+        let second = vec![mock_process(1, 15), mock_process(3, 18)];
+        let batch = tracker.observe(&second, 30_000, &exits);
+        assert_eq!(
+            events(&batch),
+            vec![
+                (LifecycleEvent::Exited, "1"),
+                (LifecycleEvent::Exited, "2"),
+                (LifecycleEvent::Started, "1"),
+                (LifecycleEvent::Started, "3"),
+            ]
+        );
+        assert_eq!(batch.records[1].lifetime_secs, 20);
+        assert_eq!(batch.records[1].command_line, "proc-2 --flag");
+
+        assert!(tracker.observe(&second, 31_000, &exits).is_empty());
+    }
+
+    #[test]
+    fn test_observe_is_bounded() {
+        let exits = ChildExits::default();
+        let mut tracker = LifecycleTracker::default();
+        tracker.observe(&[], 0, &exits);
+
+        let burst = (1..=1000)
+            .map(|id| mock_process(id, 0))
+            .collect::<Vec<ProcessInfo>>();
+        let batch = tracker.observe(&burst, 1000, &exits);
+        assert_eq!(batch.records.len(), MAX_RECORDS_PER_BATCH);
+        assert_eq!(batch.dropped_started, 1000 - MAX_RECORDS_PER_BATCH);
+
+        // Exits take priority over starts
+        let batch = tracker.observe(&burst[..100], 2000, &exits);
+        assert_eq!(batch.records.len(), MAX_RECORDS_PER_BATCH);
+        assert_eq!(batch.dropped_exited, 900 - MAX_RECORDS_PER_BATCH);
+        assert!(batch
+            .records
+            .iter()
+            .all(|record| record.event == LifecycleEvent::Exited));
+    }
+
+    #[test]
+    fn test_child_exit_status() {
+        let exits = ChildExits::default();
+        let mut sampler = Sampler::new();
+        let mut tracker = LifecycleTracker::default();
+        tracker.observe(&sampler.sample().processes, 0, &exits);
+
+        let child = Command::new("sh")
+            .args(["-c", "sleep 0.5; exit 3"])
+            .stdin(Stdio::null())
+            .spawn()
+            .unwrap();
+        let child_id = child.id().to_string();
+        exits.reap(child);
+
+        let started = tracker.observe(&sampler.sample().processes, 0, &exits);
+        let record = started
+            .records
+            .iter()
+            .find(|record| record.id == child_id)
+            .expect("Child should have been reported as started");
+        assert_eq!(record.event, LifecycleEvent::Started);
+        assert!(record.command_line.contains("exit 3"));
+
+        thread::sleep(Duration::from_secs(1));
+        let exited = tracker.observe(&sampler.sample().processes, 0, &exits);
+        let record = exited
+            .records
+            .iter()
+            .find(|record| record.id == child_id)
+            .expect("Child should have been reported as exited");
+        assert_eq!(record.event, LifecycleEvent::Exited);
+        assert_eq!(
+            record.exit,
+            Some(ExitInfo {
+                code: Some(3),
+                signal: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_child_exits_are_evicted() {
+        let exits = ChildExits::default();
+        let exit = ExitInfo {
+            code: Some(0),
+            signal: None,
+        };
+        let stale = Instant::now().checked_sub(EXIT_RETENTION).unwrap();
+        exits.statuses.lock().unwrap().insert(
+            (1, 0),
+            RecordedExit {
+                recorded_at: stale,
+                exit,
+            },
+        );
+
+        let child = Command::new("true").stdin(Stdio::null()).spawn().unwrap();
+        let child_id = child.id();
+        exits.reap(child);
+        thread::sleep(Duration::from_millis(500));
+
+        // Recording a new exit drops the stale one
+        let statuses = exits.statuses.lock().unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert!(statuses.keys().all(|(pid, _)| *pid == child_id));
+    }
+}
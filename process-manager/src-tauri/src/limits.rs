@@ -0,0 +1,471 @@
+use serde::{Deserialize, Serialize};
+use sysinfo::Pid;
+
+use crate::process::parse_pid;
+use crate::setting::SettingChange;
+
+// Share of a soft limit above which usage is flagged as near the limit
+#[cfg(target_os = "linux")]
+const NEAR_LIMIT_RATIO: f64 = 0.9;
+
+// Resource limits that can be read and changed (see getrlimit(2))
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Resource {
+    CpuTime,
+    FileSize,
+    DataSize,
+    StackSize,
+    CoreFileSize,
+    ResidentSet,
+    Processes,
+    OpenFiles,
+    LockedMemory,
+    AddressSpace,
+    FileLocks,
+    PendingSignals,
+    MsgqueueSize,
+    NicePriority,
+    RealtimePriority,
+    RealtimeTimeout,
+}
+
+// Value of a limit. Serialized as a number or "unlimited"
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitValue {
+    Unlimited,
+    #[serde(untagged)]
+    Limited(u64),
+}
+
+// Struct to contain the soft and hard values of a limit
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LimitPair {
+    soft: LimitValue,
+    hard: LimitValue,
+}
+
+// Struct to contain a single resource limit along with the process' current usage
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ResourceLimitInfo {
+    resource: Resource,
+    soft: LimitValue,
+    hard: LimitValue,
+    // e.g., "bytes", "files" or "seconds". Empty for unitless limits
+    unit: String,
+    // Current usage in `unit`s, where it can be read for the process
+    usage: Option<u64>,
+    // Set when usage is at or above 90% of the soft limit
+    near_limit: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl Resource {
+    const ALL: [Resource; 16] = [
+        Resource::CpuTime,
+        Resource::FileSize,
+        Resource::DataSize,
+        Resource::StackSize,
+        Resource::CoreFileSize,
+        Resource::ResidentSet,
+        Resource::Processes,
+        Resource::OpenFiles,
+        Resource::LockedMemory,
+        Resource::AddressSpace,
+        Resource::FileLocks,
+        Resource::PendingSignals,
+        Resource::MsgqueueSize,
+        Resource::NicePriority,
+        Resource::RealtimePriority,
+        Resource::RealtimeTimeout,
+    ];
+
+    /// Gets the name of the limit in /proc/<pid>/limits
+    fn proc_name(self) -> &'static str {
+        match self {
+            Resource::CpuTime => "Max cpu time",
+            Resource::FileSize => "Max file size",
+            Resource::DataSize => "Max data size",
+            Resource::StackSize => "Max stack size",
+            Resource::CoreFileSize => "Max core file size",
+            Resource::ResidentSet => "Max resident set",
+            Resource::Processes => "Max processes",
+            Resource::OpenFiles => "Max open files",
+            Resource::LockedMemory => "Max locked memory",
+            Resource::AddressSpace => "Max address space",
+            Resource::FileLocks => "Max file locks",
+            Resource::PendingSignals => "Max pending signals",
+            Resource::MsgqueueSize => "Max msgqueue size",
+            Resource::NicePriority => "Max nice priority",
+            Resource::RealtimePriority => "Max realtime priority",
+            Resource::RealtimeTimeout => "Max realtime timeout",
+        }
+    }
+
+    /// Gets the RLIMIT_* constant of the limit
+    fn raw(self) -> i32 {
+        (match self {
+            Resource::CpuTime => libc::RLIMIT_CPU,
+            Resource::FileSize => libc::RLIMIT_FSIZE,
+            Resource::DataSize => libc::RLIMIT_DATA,
+            Resource::StackSize => libc::RLIMIT_STACK,
+            Resource::CoreFileSize => libc::RLIMIT_CORE,
+            Resource::ResidentSet => libc::RLIMIT_RSS,
+            Resource::Processes => libc::RLIMIT_NPROC,
+            Resource::OpenFiles => libc::RLIMIT_NOFILE,
+            Resource::LockedMemory => libc::RLIMIT_MEMLOCK,
+            Resource::AddressSpace => libc::RLIMIT_AS,
+            Resource::FileLocks => libc::RLIMIT_LOCKS,
+            Resource::PendingSignals => libc::RLIMIT_SIGPENDING,
+            Resource::MsgqueueSize => libc::RLIMIT_MSGQUEUE,
+            Resource::NicePriority => libc::RLIMIT_NICE,
+            Resource::RealtimePriority => libc::RLIMIT_RTPRIO,
+            Resource::RealtimeTimeout => libc::RLIMIT_RTTIME,
+        }) as i32
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl LimitValue {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "unlimited" => Some(LimitValue::Unlimited),
+            _ => value.parse().ok().map(LimitValue::Limited),
+        }
+    }
+
+    fn from_raw(value: libc::rlim_t) -> Self {
+        if value == libc::RLIM_INFINITY {
+            LimitValue::Unlimited
+        } else {
+            LimitValue::Limited(value)
+        }
+    }
+
+    fn to_raw(self) -> libc::rlim_t {
+        match self {
+            LimitValue::Unlimited => libc::RLIM_INFINITY,
+            LimitValue::Limited(value) => value,
+        }
+    }
+}
+
+/// Parses the contents of /proc/<pid>/limits
+///
+/// Returns a vector of (resource, soft, hard, unit) tuples in table order
+#[cfg(target_os = "linux")]
+fn parse_limits(limits: &str) -> Vec<(Resource, LimitValue, LimitValue, String)> {
+    limits
+        .lines()
+        .filter_map(|line| {
+            let resource = Resource::ALL
+                .into_iter()
+                .find(|resource| line.starts_with(resource.proc_name()))?;
+            let mut values = line[resource.proc_name().len()..].split_whitespace();
+            let soft = LimitValue::parse(values.next()?)?;
+            let hard = LimitValue::parse(values.next()?)?;
+            let unit = values.next().unwrap_or_default().to_owned();
+            Some((resource, soft, hard, unit))
+        })
+        .collect()
+}
+
+/// Reads what a process currently uses of the limits that can be measured per process
+///
+/// Values are in the units of /proc/<pid>/limits. The kernel counts processes and pending signals
+/// per user, so processes aren't reported and pending signals cover every process of the owner
+///
+/// Returns a closure mapping a `Resource` to its usage
+#[cfg(target_os = "linux")]
+fn read_usage(pid: Pid) -> impl Fn(Resource) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).unwrap_or_default();
+    let status_kb = |key: &str| {
+        status.lines().find_map(|line| {
+            let value = line.strip_prefix(key)?.trim().strip_suffix("kB")?;
+            value.trim().parse::<u64>().ok().map(|kb| kb * 1024)
+        })
+    };
+    let pending_signals = status.lines().find_map(|line| {
+        let (queued, _) = line.strip_prefix("SigQ:")?.trim().split_once('/')?;
+        queued.parse::<u64>().ok()
+    });
+    let address_space = status_kb("VmSize:");
+    let resident_set = status_kb("VmRSS:");
+    let locked_memory = status_kb("VmLck:");
+    let data_size = status_kb("VmData:");
+    let stack_size = status_kb("VmStk:");
+
+    let open_files = std::fs::read_dir(format!("/proc/{pid}/fd"))
+        .ok()
+        .map(|entries| entries.count() as u64);
+    let cpu_time = std::fs::read_to_string(format!("/proc/{pid}/stat"))
+        .ok()
+        .and_then(|stat| {
+            let (_, rest) = stat.rsplit_once(')')?;
+            let fields = rest.split_whitespace().collect::<Vec<&str>>();
+            // utime and stime are fields 14 and 15, at index N - 3 once the pid and name are
+            // removed
+            let ticks =
+                fields.get(11)?.parse::<u64>().ok()? + fields.get(12)?.parse::<u64>().ok()?;
+            // SAFETY: sysconf has no preconditions
+            let ticks_per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+            Some(ticks / ticks_per_sec)
+        });
+
+    move |resource| match resource {
+        Resource::CpuTime => cpu_time,
+        Resource::DataSize => data_size,
+        Resource::StackSize => stack_size,
+        Resource::ResidentSet => resident_set,
+        Resource::OpenFiles => open_files,
+        Resource::LockedMemory => locked_memory,
+        Resource::AddressSpace => address_space,
+        Resource::PendingSignals => pending_signals,
+        _ => None,
+    }
+}
+
+/// Reads the resource limits of a process along with its usage of each
+///
+/// Returns a vector of `ResourceLimitInfo` structs in /proc/<pid>/limits order or a String error if
+/// the limits can't be read
+#[cfg(target_os = "linux")]
+pub(crate) fn read_resource_limits(pid: Pid) -> Result<Vec<ResourceLimitInfo>, String> {
+    let limits = std::fs::read_to_string(format!("/proc/{pid}/limits"))
+        .map_err(|e| format!("Failed to read resource limits of process {pid}: {e}"))?;
+    let usage_of = read_usage(pid);
+
+    Ok(parse_limits(&limits)
+        .into_iter()
+        .map(|(resource, soft, hard, unit)| {
+            let usage = usage_of(resource);
+            let near_limit = match (usage, soft) {
+                (Some(usage), LimitValue::Limited(soft)) => {
+                    usage as f64 >= soft as f64 * NEAR_LIMIT_RATIO
+                }
+                _ => false,
+            };
+            ResourceLimitInfo {
+                resource,
+                soft,
+                hard,
+                unit,
+                usage,
+                near_limit,
+            }
+        })
+        .collect())
+}
+
+/// Changes a resource limit of a process with prlimit
+///
+/// `soft` and `hard` keep their current value when `None`. Raising a hard limit requires elevated
+/// privileges (CAP_SYS_RESOURCE)
+///
+/// Returns the limit before and after the change or a String error
+#[cfg(target_os = "linux")]
+pub(crate) fn change_resource_limit(
+    pid: Pid,
+    resource: Resource,
+    soft: Option<LimitValue>,
+    hard: Option<LimitValue>,
+) -> Result<SettingChange<LimitPair>, String> {
+    let prlimit = |new: Option<&libc::rlimit>| {
+        // SAFETY: `old` is a valid rlimit to write to and `new`, when given, a valid rlimit
+        unsafe {
+            let mut old = std::mem::zeroed::<libc::rlimit>();
+            let new = new.map_or(std::ptr::null(), |new| new as *const libc::rlimit);
+            if libc::prlimit(
+                pid.as_u32() as libc::pid_t,
+                resource.raw() as _,
+                new,
+                &mut old,
+            ) != 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(LimitPair {
+                soft: LimitValue::from_raw(old.rlim_cur),
+                hard: LimitValue::from_raw(old.rlim_max),
+            })
+        }
+    };
+    let limit_error = |error: std::io::Error| match error.raw_os_error() {
+        Some(libc::ESRCH) => format!("Process with ID {pid} not found."),
+        Some(libc::EPERM) => format!(
+            "Not permitted to change the {} limit of process {pid}. Raising a hard limit or \
+             changing another user's process requires elevated privileges (CAP_SYS_RESOURCE): \
+             {error}",
+            resource.proc_name()
+        ),
+        _ => format!(
+            "Failed to change the {} limit of process {pid}: {error}",
+            resource.proc_name()
+        ),
+    };
+
+    let before = prlimit(None).map_err(limit_error)?;
+    let new = libc::rlimit {
+        rlim_cur: soft.unwrap_or(before.soft).to_raw(),
+        rlim_max: hard.unwrap_or(before.hard).to_raw(),
+    };
+    // RLIM_INFINITY is the largest value, so this also rejects a finite hard limit below an
+    // unlimited soft one
+    if new.rlim_cur > new.rlim_max {
+        return Err(format!(
+            "Soft limit ({:?}) must not exceed hard limit ({:?})",
+            LimitValue::from_raw(new.rlim_cur),
+            LimitValue::from_raw(new.rlim_max)
+        ));
+    }
+    prlimit(Some(&new)).map_err(limit_error)?;
+
+    Ok(SettingChange {
+        id: pid.to_string(),
+        before,
+        after: prlimit(None).map_err(limit_error)?,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn read_resource_limits(_pid: Pid) -> Result<Vec<ResourceLimitInfo>, String> {
+    Err("Reading resource limits is only supported on Linux".to_owned())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn change_resource_limit(
+    _pid: Pid,
+    _resource: Resource,
+    _soft: Option<LimitValue>,
+    _hard: Option<LimitValue>,
+) -> Result<SettingChange<LimitPair>, String> {
+    Err("Changing resource limits is only supported on Linux".to_owned())
+}
+
+/// Gets the resource limits of a process and its current usage of each
+///
+/// `id` is the Pid of the process
+///
+/// Returns a vector of `ResourceLimitInfo` structs if successful and a String error if unsuccessful
+#[tauri::command]
+pub async fn get_resource_limits(id: &str) -> Result<Vec<ResourceLimitInfo>, String> {
+    read_resource_limits(parse_pid(id)?)
+}
+
+/// Changes a resource limit of a process, like `prlimit --pid`
+///
+/// `id` is the Pid of the process. `soft` and `hard` are numbers or "unlimited" and keep their
+/// current value when omitted. The soft limit may not exceed the hard limit
+///
+/// Returns the limit before and after the change if successful and a String error if unsuccessful
+#[tauri::command]
+pub async fn set_resource_limit(
+    id: &str,
+    resource: Resource,
+    soft: Option<LimitValue>,
+    hard: Option<LimitValue>,
+) -> Result<SettingChange<LimitPair>, String> {
+    change_resource_limit(parse_pid(id)?, resource, soft, hard)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::process::spawn_dummy_process;
+
+    #[test]
+    fn test_parse_limits() {
+        let limits = "\
+Limit                     Soft Limit           Hard Limit           Units
+Max cpu time              unlimited            unlimited            seconds
+Max open files            1024                 524288               files
+Max nice priority         0                    0
+";
+        assert_eq!(
+            parse_limits(limits),
+            vec![
+                (
+                    Resource::CpuTime,
+                    LimitValue::Unlimited,
+                    LimitValue::Unlimited,
+                    "seconds".to_owned()
+                ),
+                (
+                    Resource::OpenFiles,
+                    LimitValue::Limited(1024),
+                    LimitValue::Limited(524288),
+                    "files".to_owned()
+                ),
+                (
+                    Resource::NicePriority,
+                    LimitValue::Limited(0),
+                    LimitValue::Limited(0),
+                    String::new()
+                ),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&LimitPair {
+                soft: LimitValue::Limited(8),
+                hard: LimitValue::Unlimited,
+            })
+            .unwrap(),
+            r#"{"soft":8,"hard":"unlimited"}"#
+        );
+    }
+
+    #[test]
+    fn test_resource_limits() {
+        let mut child = spawn_dummy_process();
+        let id = child.id().to_string();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        // Wait for exec to close the descriptors inherited from the test process
+        std::thread::sleep(std::time::Duration::from_millis(200));
+
+        let limits = rt.block_on(get_resource_limits(&id)).unwrap();
+        assert_eq!(limits.len(), Resource::ALL.len());
+        let open_files = |limits: &[ResourceLimitInfo]| {
+            limits
+                .iter()
+                .find(|limit| limit.resource == Resource::OpenFiles)
+                .cloned()
+                .unwrap()
+        };
+        let before = open_files(&limits);
+        let usage = before.usage.unwrap();
+        assert!(usage >= 3);
+        assert!(!before.near_limit);
+
+        // Lower the soft limit to the current fd count so that usage is flagged
+        let change = rt
+            .block_on(set_resource_limit(
+                &id,
+                Resource::OpenFiles,
+                Some(LimitValue::Limited(usage)),
+                None,
+            ))
+            .unwrap();
+        assert_eq!(change.before.soft, before.soft);
+        assert_eq!(change.after.soft, LimitValue::Limited(usage));
+        assert_eq!(change.after.hard, before.hard);
+        let after = open_files(&rt.block_on(get_resource_limits(&id)).unwrap());
+        assert!(after.near_limit);
+
+        assert!(rt
+            .block_on(set_resource_limit(
+                &id,
+                Resource::CoreFileSize,
+                Some(LimitValue::Unlimited),
+                Some(LimitValue::Limited(0)),
+            ))
+            .unwrap_err()
+            .contains("must not exceed hard limit"));
+        assert!(rt
+            .block_on(get_resource_limits("999999999"))
+            .unwrap_err()
+            .contains("Failed to read resource limits of process 999999999"));
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
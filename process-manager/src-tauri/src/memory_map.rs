@@ -0,0 +1,352 @@
+use serde::{Deserialize, Serialize};
+use sysinfo::Pid;
+
+use crate::process::parse_pid;
+
+// Struct to contain the memory counters of a mapping, group of mappings or whole process
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct MemoryUsage {
+    // Virtual size. Not reported by smaps_rollup, so 0 in totals read from it
+    size_bytes: u64,
+    rss_bytes: u64,
+    // Proportional set size: private pages plus an equal share of each shared page
+    pss_bytes: u64,
+    // Unique set size: pages no other process maps (private clean + private dirty)
+    uss_bytes: u64,
+    shared_clean_bytes: u64,
+    shared_dirty_bytes: u64,
+    private_clean_bytes: u64,
+    private_dirty_bytes: u64,
+    swap_bytes: u64,
+}
+
+// What backs a group of mappings
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MappingKind {
+    File,
+    Heap,
+    Stack,
+    Anonymous,
+    // Kernel provided mappings (e.g., "[vdso]", "[vvar]")
+    Special,
+}
+
+// Struct to contain a single mapping from /proc/<pid>/smaps
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MemoryMapping {
+    // Hex address range as printed by the kernel (e.g., "7f3a1c000000-7f3a1c021000")
+    address_range: String,
+    // e.g., "r-xp"
+    permissions: String,
+    offset: u64,
+    usage: MemoryUsage,
+}
+
+// Struct to contain the mappings that share a backing file or label
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MappingGroup {
+    // Path of the backing file or a label (e.g., "[heap]", "[anonymous]")
+    name: String,
+    kind: MappingKind,
+    usage: MemoryUsage,
+    mappings: Vec<MemoryMapping>,
+}
+
+// Struct to contain the memory map of a process
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MemoryMap {
+    id: String,
+    total: MemoryUsage,
+    // Sorted by PSS, largest first
+    groups: Vec<MappingGroup>,
+}
+
+impl MemoryUsage {
+    /// Sets the counter for an smaps field
+    ///
+    /// `key` is the field name without the colon and `kb` its value in kB. Unknown fields are
+    /// ignored
+    #[cfg(target_os = "linux")]
+    fn set(&mut self, key: &str, kb: u64) {
+        let bytes = kb * 1024;
+        match key {
+            "Size" => self.size_bytes = bytes,
+            "Rss" => self.rss_bytes = bytes,
+            "Pss" => self.pss_bytes = bytes,
+            "Shared_Clean" => self.shared_clean_bytes = bytes,
+            "Shared_Dirty" => self.shared_dirty_bytes = bytes,
+            "Private_Clean" => self.private_clean_bytes = bytes,
+            "Private_Dirty" => self.private_dirty_bytes = bytes,
+            "Swap" => self.swap_bytes = bytes,
+            _ => return,
+        }
+        self.uss_bytes = self.private_clean_bytes + self.private_dirty_bytes;
+    }
+
+    #[cfg(target_os = "linux")]
+    fn add(&mut self, other: &MemoryUsage) {
+        self.size_bytes += other.size_bytes;
+        self.rss_bytes += other.rss_bytes;
+        self.pss_bytes += other.pss_bytes;
+        self.uss_bytes += other.uss_bytes;
+        self.shared_clean_bytes += other.shared_clean_bytes;
+        self.shared_dirty_bytes += other.shared_dirty_bytes;
+        self.private_clean_bytes += other.private_clean_bytes;
+        self.private_dirty_bytes += other.private_dirty_bytes;
+        self.swap_bytes += other.swap_bytes;
+    }
+
+    pub(crate) fn pss_bytes(&self) -> u64 {
+        self.pss_bytes
+    }
+
+    pub(crate) fn uss_bytes(&self) -> u64 {
+        self.uss_bytes
+    }
+}
+
+/// Works out how a mapping is grouped from the pathname column of smaps
+///
+/// Returns the group name and `MappingKind`
+#[cfg(target_os = "linux")]
+fn classify_mapping(pathname: &str) -> (String, MappingKind) {
+    let kind = match pathname {
+        "" => return ("[anonymous]".to_owned(), MappingKind::Anonymous),
+        "[heap]" => MappingKind::Heap,
+        // Older kernels label thread stacks "[stack:<tid>]"
+        _ if pathname.starts_with("[stack") => MappingKind::Stack,
+        // Named anonymous mappings (e.g., "[anon:jemalloc]")
+        _ if pathname.starts_with("[anon") => MappingKind::Anonymous,
+        _ if pathname.starts_with('[') => MappingKind::Special,
+        _ => MappingKind::File,
+    };
+    (pathname.to_owned(), kind)
+}
+
+/// Parses the contents of /proc/<pid>/smaps or /proc/<pid>/smaps_rollup
+///
+/// Returns a vector of (header fields, usage) pairs, one for each mapping
+#[cfg(target_os = "linux")]
+fn parse_smaps(smaps: &str) -> Vec<(Vec<&str>, MemoryUsage)> {
+    let mut mappings: Vec<(Vec<&str>, MemoryUsage)> = Vec::new();
+
+    for line in smaps.lines() {
+        // Header lines start with an address range, field lines with a name
+        let first = line.split_whitespace().next().unwrap_or_default();
+        if first.contains('-') && first.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+            // address perms offset dev inode pathname (which may contain spaces)
+            let mut header = line.splitn(6, char::is_whitespace).collect::<Vec<&str>>();
+            if let Some(pathname) = header.get_mut(5) {
+                *pathname = pathname.trim();
+            }
+            mappings.push((header, MemoryUsage::default()));
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Some(kb) = value
+            .trim()
+            .strip_suffix("kB")
+            .and_then(|kb| kb.trim().parse::<u64>().ok())
+        else {
+            continue;
+        };
+        if let Some((_, usage)) = mappings.last_mut() {
+            usage.set(key, kb);
+        }
+    }
+    mappings
+}
+
+/// Reads the memory totals of a process from /proc/<pid>/smaps_rollup
+///
+/// Falls back to summing /proc/<pid>/smaps on kernels without smaps_rollup
+///
+/// Returns the `MemoryUsage` or a String error if neither file can be read
+#[cfg(target_os = "linux")]
+pub(crate) fn read_memory_usage(pid: Pid) -> Result<MemoryUsage, String> {
+    let contents = std::fs::read_to_string(format!("/proc/{pid}/smaps_rollup"))
+        .or_else(|_| std::fs::read_to_string(format!("/proc/{pid}/smaps")))
+        .map_err(|e| format!("Failed to read memory map of process {pid}: {e}"))?;
+
+    let mut total = MemoryUsage::default();
+    for (_, usage) in parse_smaps(&contents) {
+        total.add(&usage);
+    }
+    Ok(total)
+}
+
+/// Reads the mappings of a process and groups them by backing file
+///
+/// `pid` is the Pid of the process. Reading another user's process requires the same permission
+/// as attaching a debugger to it
+///
+/// Returns a `MemoryMap` struct or a String error if the mappings can't be read
+#[cfg(target_os = "linux")]
+pub(crate) fn read_memory_map(pid: Pid) -> Result<MemoryMap, String> {
+    let smaps = std::fs::read_to_string(format!("/proc/{pid}/smaps"))
+        .map_err(|e| format!("Failed to read memory map of process {pid}: {e}"))?;
+
+    let mut groups: Vec<MappingGroup> = Vec::new();
+    let mut group_index = std::collections::HashMap::new();
+    let mut mapped_total = MemoryUsage::default();
+    for (header, usage) in parse_smaps(&smaps) {
+        let (name, kind) = classify_mapping(header.get(5).copied().unwrap_or_default());
+        mapped_total.add(&usage);
+
+        let index = *group_index.entry(name.clone()).or_insert_with(|| {
+            groups.push(MappingGroup {
+                name,
+                kind,
+                usage: MemoryUsage::default(),
+                mappings: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        let group = &mut groups[index];
+        group.usage.add(&usage);
+        group.mappings.push(MemoryMapping {
+            address_range: header.first().copied().unwrap_or_default().to_owned(),
+            permissions: header.get(1).copied().unwrap_or_default().to_owned(),
+            offset: header
+                .get(2)
+                .and_then(|offset| u64::from_str_radix(offset, 16).ok())
+                .unwrap_or_default(),
+            usage,
+        });
+    }
+    groups.sort_by_key(|group| std::cmp::Reverse(group.usage.pss_bytes));
+
+    // smaps_rollup is read in one pass, so its totals are consistent even if mappings changed
+    // while smaps was being read. It has no sizes, so keep the summed one
+    let total = match read_memory_usage(pid) {
+        Ok(rollup) => MemoryUsage {
+            size_bytes: mapped_total.size_bytes,
+            ..rollup
+        },
+        Err(_) => mapped_total,
+    };
+
+    Ok(MemoryMap {
+        id: pid.to_string(),
+        total,
+        groups,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn read_memory_usage(_pid: Pid) -> Result<MemoryUsage, String> {
+    Err("Reading memory maps is only supported on Linux".to_owned())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn read_memory_map(_pid: Pid) -> Result<MemoryMap, String> {
+    Err("Reading memory maps is only supported on Linux".to_owned())
+}
+
+/// Gets the memory map of a process with RSS, PSS, USS, shared/private clean/dirty and swap for
+/// every mapping
+///
+/// `id` is the Pid of the process
+///
+/// Returns a `MemoryMap` struct with mappings grouped by backing file if successful and a String
+/// error if unsuccessful
+#[tauri::command]
+pub async fn get_memory_map(id: &str) -> Result<MemoryMap, String> {
+    read_memory_map(parse_pid(id)?)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_smaps() {
+        let smaps = "\
+55d0a1b2c000-55d0a1b2e000 r--p 00000000 08:01 1234                       /usr/bin/my app
+Size:                  8 kB
+Rss:                   8 kB
+Pss:                   4 kB
+Shared_Clean:          4 kB
+Shared_Dirty:          0 kB
+Private_Clean:         4 kB
+Private_Dirty:         0 kB
+Swap:                  0 kB
+VmFlags: rd mr mw me dw sd
+55d0a2000000-55d0a2021000 rw-p 00000000 00:00 0                          [heap]
+Size:                132 kB
+Rss:                  16 kB
+Pss:                  16 kB
+Private_Dirty:        16 kB
+Swap:                  8 kB
+7ffd1000000-7ffd1021000 rw-p 00000000 00:00 0
+Rss:                   4 kB
+Private_Dirty:         4 kB
+";
+        let mappings = parse_smaps(smaps);
+        assert_eq!(mappings.len(), 3);
+
+        let (header, usage) = &mappings[0];
+        assert_eq!(header[5], "/usr/bin/my app");
+        assert_eq!(usage.rss_bytes, 8 * 1024);
+        assert_eq!(usage.pss_bytes, 4 * 1024);
+        assert_eq!(usage.uss_bytes, 4 * 1024);
+        assert_eq!(usage.shared_clean_bytes, 4 * 1024);
+
+        let (header, usage) = &mappings[1];
+        assert_eq!(classify_mapping(header[5]).1, MappingKind::Heap);
+        assert_eq!(usage.uss_bytes, 16 * 1024);
+        assert_eq!(usage.swap_bytes, 8 * 1024);
+
+        let (header, _) = &mappings[2];
+        assert_eq!(
+            classify_mapping(header.get(5).copied().unwrap_or_default()),
+            ("[anonymous]".to_owned(), MappingKind::Anonymous)
+        );
+        assert_eq!(classify_mapping("[stack]").1, MappingKind::Stack);
+        assert_eq!(classify_mapping("[vdso]").1, MappingKind::Special);
+    }
+
+    #[test]
+    fn test_get_memory_map() {
+        // Touch some heap memory so the heap group isn't empty
+        let buffer = vec![1u8; 4 * 1024 * 1024];
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let memory_map = rt
+            .block_on(get_memory_map(&std::process::id().to_string()))
+            .unwrap();
+        assert!(buffer.iter().all(|byte| *byte == 1));
+
+        assert!(memory_map.total.rss_bytes > 4 * 1024 * 1024);
+        assert!(memory_map.total.pss_bytes > 0);
+        assert!(memory_map.total.uss_bytes <= memory_map.total.rss_bytes);
+        assert!(memory_map
+            .groups
+            .iter()
+            .any(|group| group.kind == MappingKind::Stack));
+        assert!(memory_map
+            .groups
+            .iter()
+            .any(|group| group.kind == MappingKind::File && group.name.starts_with('/')));
+        assert!(memory_map
+            .groups
+            .windows(2)
+            .all(|pair| pair[0].usage.pss_bytes >= pair[1].usage.pss_bytes));
+
+        let mapping_count = memory_map
+            .groups
+            .iter()
+            .map(|group| group.mappings.len())
+            .sum::<usize>();
+        assert!(mapping_count >= memory_map.groups.len());
+
+        assert!(rt
+            .block_on(get_memory_map("999999999"))
+            .unwrap_err()
+            .contains("Failed to read memory map of process 999999999"));
+    }
+}
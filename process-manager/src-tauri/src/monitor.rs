@@ -0,0 +1,695 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime::{self, JoinHandle};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::watch;
+use tokio::time::{interval, Interval, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+
+// Polling interval used when a start command doesn't specify one
+pub(crate) const DEFAULT_MONITOR_INTERVAL: Duration = Duration::from_millis(1000);
+// Shortest polling interval a monitor may request
+const MINIMUM_MONITOR_INTERVAL: Duration = Duration::from_millis(250);
+// Adaptive monitors poll this many times slower while the window is hidden or minimized
+const BACKGROUND_SLOWDOWN: u32 = 5;
+
+// Event payload versions. Version 1 emits the bare data so older frontends keep working
+const LEGACY_PAYLOAD_VERSION: u32 = 1;
+const LATEST_PAYLOAD_VERSION: u32 = 2;
+
+// Envelope wrapping event data from payload version 2 onwards
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VersionedPayload<T> {
+    version: u32,
+    // Unix time in milliseconds of the snapshot the data was taken from
+    timestamp_ms: u64,
+    data: T,
+}
+
+// Type of data a monitor emits
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorKind {
+    Processes,
+    System,
+    Lifecycle,
+    Threads,
+    Network,
+    Disks,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorState {
+    Running,
+    Paused,
+}
+
+// Struct to contain the status of a single monitor as reported to the frontend
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MonitorStatus {
+    id: u64,
+    kind: MonitorKind,
+    state: MonitorState,
+    interval_ms: u64,
+    // Interval currently in effect, which differs from `interval_ms` for backgrounded adaptive
+    // monitors
+    effective_interval_ms: u64,
+    adaptive: bool,
+}
+
+// Handle to a running monitor loop
+struct MonitorHandle {
+    kind: MonitorKind,
+    token: CancellationToken,
+    paused: Arc<AtomicBool>,
+    period: Duration,
+    adaptive: bool,
+    effective_period: watch::Sender<Duration>,
+    task: JoinHandle<()>,
+}
+
+// Registry of every running monitor, keyed by monitor ID
+#[derive(Default)]
+pub struct MonitorRegistry {
+    next_id: u64,
+    monitors: HashMap<u64, MonitorHandle>,
+    // Set while the main window is hidden or minimized
+    background: bool,
+    // Retunes the shared sampler to match the fastest active monitor
+    sampler_period: Option<mpsc::Sender<Duration>>,
+    // Last period sent to the sampler, so that it is only told about changes
+    sent_sampler_period: Option<Duration>,
+}
+
+/// Validates a polling interval received from the frontend
+///
+/// `interval_ms` is the requested interval in milliseconds, defaulting to
+/// `DEFAULT_MONITOR_INTERVAL`
+///
+/// Returns the interval as a `Duration` or a String error if it is too short
+pub(crate) fn monitor_period(interval_ms: Option<u64>) -> Result<Duration, String> {
+    let Some(interval_ms) = interval_ms else {
+        return Ok(DEFAULT_MONITOR_INTERVAL);
+    };
+
+    let period = Duration::from_millis(interval_ms);
+    if period < MINIMUM_MONITOR_INTERVAL {
+        return Err(format!(
+            "Monitor interval of {interval_ms} ms is shorter than the minimum of {} ms.",
+            MINIMUM_MONITOR_INTERVAL.as_millis()
+        ));
+    }
+    Ok(period)
+}
+
+/// Validates the event payload version requested by a start command
+///
+/// `version` defaults to the legacy version when `None`
+///
+/// Returns the version or a String error if it isn't supported
+pub(crate) fn event_payload_version(version: Option<u32>) -> Result<u32, String> {
+    let version = version.unwrap_or(LEGACY_PAYLOAD_VERSION);
+    if (LEGACY_PAYLOAD_VERSION..=LATEST_PAYLOAD_VERSION).contains(&version) {
+        Ok(version)
+    } else {
+        Err(format!(
+            "Unsupported payload version ({version}). Expected {LEGACY_PAYLOAD_VERSION} to \
+             {LATEST_PAYLOAD_VERSION}"
+        ))
+    }
+}
+
+/// Emits monitor data in the shape of the requested payload version
+///
+/// `timestamp_ms` is the time of the snapshot the data was taken from
+///
+/// Returns a Unit Type if successful or a Tauri error if the event can't be emitted
+pub(crate) fn emit_payload<T: Serialize + Clone>(
+    app: &AppHandle,
+    event: &str,
+    version: u32,
+    timestamp_ms: u64,
+    data: T,
+) -> tauri::Result<()> {
+    if version == LEGACY_PAYLOAD_VERSION {
+        app.emit(event, data)
+    } else {
+        app.emit(
+            event,
+            VersionedPayload {
+                version,
+                timestamp_ms,
+                data,
+            },
+        )
+    }
+}
+
+/// Creates an interval timer that delays rather than bursts after a slow tick
+fn monitor_interval(period: Duration) -> Interval {
+    let mut interval_timer = interval(period);
+    interval_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval_timer
+}
+
+/// Runs `tick` on every `period` until `token` is cancelled
+///
+/// Ticks are skipped while `paused` is set, but the loop keeps waiting for cancellation. A new
+/// period takes effect immediately, starting with a tick. Ticks can read files under /proc and
+/// serialize large payloads, so each one runs on the blocking pool and the loop waits for it
+async fn run_monitor<F>(
+    mut period: watch::Receiver<Duration>,
+    token: CancellationToken,
+    paused: Arc<AtomicBool>,
+    mut tick: F,
+) where
+    F: FnMut() + Send + 'static,
+{
+    let mut interval_timer = monitor_interval(*period.borrow_and_update());
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            Ok(()) = period.changed() => {
+                interval_timer = monitor_interval(*period.borrow_and_update());
+                continue;
+            }
+            _ = interval_timer.tick() => {}
+        }
+
+        if !paused.load(Ordering::Relaxed) {
+            tick = match async_runtime::spawn_blocking(move || {
+                tick();
+                tick
+            })
+            .await
+            {
+                Ok(tick) => tick,
+                Err(e) => {
+                    eprintln!("Monitor tick failed. Error: {e}");
+                    break;
+                }
+            };
+        }
+    }
+}
+
+impl MonitorRegistry {
+    /// Creates an empty registry that keeps the shared sampler in step with its monitors
+    ///
+    /// `sampler_period` is the sender returned by `SamplerState::period_sender`
+    pub(crate) fn new(sampler_period: mpsc::Sender<Duration>) -> Self {
+        MonitorRegistry {
+            sampler_period: Some(sampler_period),
+            ..Default::default()
+        }
+    }
+
+    /// Gets the interval a monitor should currently poll at
+    fn effective_period(&self, handle: &MonitorHandle) -> Duration {
+        if handle.adaptive && self.background {
+            handle.period * BACKGROUND_SLOWDOWN
+        } else {
+            handle.period
+        }
+    }
+
+    /// Pushes the current effective interval to every monitor loop and the shared sampler
+    fn retune(&mut self) {
+        for handle in self.monitors.values() {
+            handle.effective_period.send_if_modified(|period| {
+                let effective_period = self.effective_period(handle);
+                let modified = *period != effective_period;
+                *period = effective_period;
+                modified
+            });
+        }
+
+        // The sampler only needs to refresh as often as the fastest unpaused monitor polls
+        let fastest = self
+            .monitors
+            .values()
+            .filter(|handle| !handle.paused.load(Ordering::Relaxed))
+            .map(|handle| self.effective_period(handle))
+            .min()
+            .unwrap_or(DEFAULT_MONITOR_INTERVAL);
+        if let Some(sampler_period) = &self.sampler_period {
+            if self.sent_sampler_period != Some(fastest) {
+                let _ = sampler_period.send(fastest);
+                self.sent_sampler_period = Some(fastest);
+            }
+        }
+    }
+
+    /// Starts a monitor loop, replacing any monitor of the same kind
+    ///
+    /// `kind` identifies what the monitor emits, `period` is the time between ticks, `adaptive`
+    /// slows the monitor down while the window is in the background and `tick` is called on every
+    /// unpaused tick
+    ///
+    /// Returns the ID of the new monitor
+    pub(crate) fn start<F>(
+        &mut self,
+        kind: MonitorKind,
+        period: Duration,
+        adaptive: bool,
+        tick: F,
+    ) -> u64
+    where
+        F: FnMut() + Send + 'static,
+    {
+        // Only one monitor of each kind may run at a time
+        self.stop_kind(kind);
+
+        let token = CancellationToken::new();
+        let paused = Arc::new(AtomicBool::new(false));
+        let (effective_period, period_receiver) = watch::channel(period);
+        let task = async_runtime::spawn(run_monitor(
+            period_receiver,
+            token.clone(),
+            paused.clone(),
+            tick,
+        ));
+
+        self.next_id += 1;
+        self.monitors.insert(
+            self.next_id,
+            MonitorHandle {
+                kind,
+                token,
+                paused,
+                period,
+                adaptive,
+                effective_period,
+                task,
+            },
+        );
+        self.retune();
+        self.next_id
+    }
+
+    /// Cancels a monitor loop and removes it from the registry
+    ///
+    /// `id` is the ID of the monitor to be stopped
+    ///
+    /// Returns the monitor's task handle, which finishes once the loop has exited, or a String
+    /// error
+    pub(crate) fn stop(&mut self, id: u64) -> Result<JoinHandle<()>, String> {
+        let Some(handle) = self.monitors.remove(&id) else {
+            return Err(format!("Monitor with ID {id} not found."));
+        };
+
+        handle.token.cancel();
+        self.retune();
+        Ok(handle.task)
+    }
+
+    /// Cancels every monitor of a given kind
+    ///
+    /// `kind` is the kind of monitor to be stopped
+    pub(crate) fn stop_kind(&mut self, kind: MonitorKind) {
+        self.monitors.retain(|_, handle| {
+            if handle.kind == kind {
+                handle.token.cancel();
+            }
+            handle.kind != kind
+        });
+        self.retune();
+    }
+
+    /// Pauses or resumes a monitor without cancelling its loop
+    ///
+    /// `id` is the ID of the monitor and `paused` is its new state
+    ///
+    /// Returns a Unit Type if successful or a String error if the monitor doesn't exist
+    pub(crate) fn set_paused(&mut self, id: u64, paused: bool) -> Result<(), String> {
+        let Some(handle) = self.monitors.get(&id) else {
+            return Err(format!("Monitor with ID {id} not found."));
+        };
+
+        handle.paused.store(paused, Ordering::Relaxed);
+        self.retune();
+        Ok(())
+    }
+
+    /// Changes how often a running monitor polls without restarting it
+    ///
+    /// `id` is the ID of the monitor, `period` is its new interval and `adaptive` replaces its
+    /// adaptive setting when given
+    ///
+    /// Returns a Unit Type if successful or a String error if the monitor doesn't exist
+    pub(crate) fn set_period(
+        &mut self,
+        id: u64,
+        period: Duration,
+        adaptive: Option<bool>,
+    ) -> Result<(), String> {
+        let Some(handle) = self.monitors.get_mut(&id) else {
+            return Err(format!("Monitor with ID {id} not found."));
+        };
+
+        handle.period = period;
+        handle.adaptive = adaptive.unwrap_or(handle.adaptive);
+        self.retune();
+        Ok(())
+    }
+
+    /// Records whether the main window is in the background and retunes adaptive monitors
+    ///
+    /// `background` is true while the window is hidden or minimized
+    pub(crate) fn set_background(&mut self, background: bool) {
+        if self.background != background {
+            self.background = background;
+            self.retune();
+        }
+    }
+
+    /// Gets the status of every registered monitor
+    ///
+    /// Returns a vector of `MonitorStatus` structs sorted by ID
+    pub(crate) fn status(&self) -> Vec<MonitorStatus> {
+        let mut statuses = self
+            .monitors
+            .iter()
+            .map(|(id, handle)| MonitorStatus {
+                id: *id,
+                kind: handle.kind,
+                state: if handle.paused.load(Ordering::Relaxed) {
+                    MonitorState::Paused
+                } else {
+                    MonitorState::Running
+                },
+                interval_ms: handle.period.as_millis() as u64,
+                effective_interval_ms: self.effective_period(handle).as_millis() as u64,
+                adaptive: handle.adaptive,
+            })
+            .collect::<Vec<MonitorStatus>>();
+
+        statuses.sort_by_key(|status| status.id);
+        statuses
+    }
+}
+
+/// Stops a monitor by ID
+///
+/// `registry` is a reference to the MonitorRegistry injected by Tauri
+///
+/// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
+#[tauri::command]
+pub fn stop_monitor(registry: State<'_, Mutex<MonitorRegistry>>, id: u64) -> Result<(), String> {
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    registry_guard.stop(id).map(|_| ())
+}
+
+/// Pauses a monitor so that it stops emitting events until it is resumed
+///
+/// `registry` is a reference to the MonitorRegistry injected by Tauri
+///
+/// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
+#[tauri::command]
+pub fn pause_monitor(registry: State<'_, Mutex<MonitorRegistry>>, id: u64) -> Result<(), String> {
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    registry_guard.set_paused(id, true)
+}
+
+/// Resumes a paused monitor
+///
+/// `registry` is a reference to the MonitorRegistry injected by Tauri
+///
+/// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
+#[tauri::command]
+pub fn resume_monitor(registry: State<'_, Mutex<MonitorRegistry>>, id: u64) -> Result<(), String> {
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    registry_guard.set_paused(id, false)
+}
+
+/// Changes the polling interval of a running monitor
+///
+/// `registry` is a reference to the MonitorRegistry injected by Tauri
+///
+/// `interval_ms` is the new interval in milliseconds and `adaptive` optionally turns adaptive
+/// polling on or off
+///
+/// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
+#[tauri::command]
+pub fn set_monitor_interval(
+    registry: State<'_, Mutex<MonitorRegistry>>,
+    id: u64,
+    interval_ms: u64,
+    adaptive: Option<bool>,
+) -> Result<(), String> {
+    let period = monitor_period(Some(interval_ms))?;
+
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    registry_guard.set_period(id, period, adaptive)
+}
+
+/// Gets the status of every running or paused monitor
+///
+/// `registry` is a reference to the MonitorRegistry injected by Tauri
+///
+/// Returns a Result with a vector of `MonitorStatus` structs or a String error
+#[tauri::command]
+pub fn get_monitor_status(
+    registry: State<'_, Mutex<MonitorRegistry>>,
+) -> Result<Vec<MonitorStatus>, String> {
+    let Ok(registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    Ok(registry_guard.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn counting_tick(counter: &Arc<AtomicUsize>) -> impl FnMut() + Send + 'static {
+        let counter = counter.clone();
+        move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_start_stop_restart() {
+        let mut registry = MonitorRegistry::default();
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        for round in 1..=5 {
+            let id = registry.start(
+                MonitorKind::Processes,
+                Duration::from_millis(10),
+                false,
+                counting_tick(&counter),
+            );
+            assert_eq!(id, round);
+            assert_eq!(registry.status().len(), 1);
+
+            // Wait for the loop to tick at least once
+            let before = counter.load(Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            assert!(counter.load(Ordering::SeqCst) > before);
+
+            let task = registry.stop(id).unwrap();
+            async_runtime::block_on(task).unwrap();
+            assert!(registry.status().is_empty());
+
+            // No ticks after the loop has exited
+            let after = counter.load(Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            assert_eq!(counter.load(Ordering::SeqCst), after);
+        }
+
+        assert!(registry
+            .stop(1)
+            .unwrap_err()
+            .contains("Monitor with ID 1 not found."));
+    }
+
+    #[test]
+    fn test_start_replaces_same_kind() {
+        let mut registry = MonitorRegistry::default();
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let first = registry.start(
+            MonitorKind::System,
+            Duration::from_millis(10),
+            false,
+            counting_tick(&counter),
+        );
+        let second = registry.start(
+            MonitorKind::System,
+            Duration::from_millis(10),
+            false,
+            counting_tick(&counter),
+        );
+        let other = registry.start(
+            MonitorKind::Processes,
+            Duration::from_millis(10),
+            false,
+            counting_tick(&counter),
+        );
+
+        let ids = registry
+            .status()
+            .iter()
+            .map(|status| status.id)
+            .collect::<Vec<u64>>();
+        assert_eq!(ids, vec![second, other]);
+        assert!(registry.stop(first).is_err());
+
+        registry.stop_kind(MonitorKind::System);
+        registry.stop_kind(MonitorKind::Processes);
+        assert!(registry.status().is_empty());
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let mut registry = MonitorRegistry::default();
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let id = registry.start(
+            MonitorKind::Processes,
+            Duration::from_millis(10),
+            false,
+            counting_tick(&counter),
+        );
+
+        registry.set_paused(id, true).unwrap();
+        assert_eq!(registry.status()[0].state, MonitorState::Paused);
+        // Let any tick that was already running finish
+        std::thread::sleep(Duration::from_millis(20));
+        let paused_count = counter.load(Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(counter.load(Ordering::SeqCst), paused_count);
+
+        registry.set_paused(id, false).unwrap();
+        assert_eq!(registry.status()[0].state, MonitorState::Running);
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(counter.load(Ordering::SeqCst) > paused_count);
+
+        async_runtime::block_on(registry.stop(id).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_set_period() {
+        let mut registry = MonitorRegistry::default();
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let id = registry.start(
+            MonitorKind::System,
+            Duration::from_secs(3600),
+            false,
+            counting_tick(&counter),
+        );
+        // Only the immediate first tick happens at the long interval
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        registry
+            .set_period(id, Duration::from_millis(10), None)
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(counter.load(Ordering::SeqCst) > 2);
+        assert_eq!(registry.status()[0].interval_ms, 10);
+
+        assert!(registry
+            .set_period(id + 1, Duration::from_millis(10), None)
+            .is_err());
+        async_runtime::block_on(registry.stop(id).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_adaptive_background() {
+        let (sampler_period, sampler_receiver) = mpsc::channel();
+        let mut registry = MonitorRegistry::new(sampler_period);
+
+        let adaptive = registry.start(
+            MonitorKind::Processes,
+            Duration::from_millis(500),
+            true,
+            || {},
+        );
+        let fixed = registry.start(
+            MonitorKind::System,
+            Duration::from_millis(2000),
+            false,
+            || {},
+        );
+        assert_eq!(
+            sampler_receiver.try_iter().last(),
+            Some(Duration::from_millis(500))
+        );
+
+        registry.set_background(true);
+        let statuses = registry.status();
+        assert_eq!(statuses[0].effective_interval_ms, 2500);
+        assert_eq!(statuses[1].effective_interval_ms, 2000);
+        // The sampler follows the fastest monitor
+        assert_eq!(
+            sampler_receiver.try_iter().last(),
+            Some(Duration::from_millis(2000))
+        );
+
+        registry.set_background(false);
+        assert_eq!(registry.status()[0].effective_interval_ms, 500);
+        assert_eq!(
+            sampler_receiver.try_iter().last(),
+            Some(Duration::from_millis(500))
+        );
+
+        // Paused monitors don't keep the sampler busy
+        registry.set_paused(adaptive, true).unwrap();
+        assert_eq!(
+            sampler_receiver.try_iter().last(),
+            Some(Duration::from_millis(2000))
+        );
+        // Commands that leave the fastest period alone don't disturb the sampler
+        registry.set_paused(adaptive, true).unwrap();
+        registry.set_paused(fixed, false).unwrap();
+        assert_eq!(sampler_receiver.try_iter().count(), 0);
+
+        registry.stop(adaptive).unwrap();
+        registry.stop(fixed).unwrap();
+        assert_eq!(
+            sampler_receiver.try_iter().last(),
+            Some(DEFAULT_MONITOR_INTERVAL)
+        );
+    }
+
+    #[test]
+    fn test_monitor_period() {
+        assert_eq!(monitor_period(None).unwrap(), DEFAULT_MONITOR_INTERVAL);
+        assert_eq!(
+            monitor_period(Some(500)).unwrap(),
+            Duration::from_millis(500)
+        );
+        assert!(monitor_period(Some(10))
+            .unwrap_err()
+            .contains("shorter than the minimum of 250 ms"));
+    }
+
+    #[test]
+    fn test_event_payload_version() {
+        assert_eq!(event_payload_version(None).unwrap(), LEGACY_PAYLOAD_VERSION);
+        assert_eq!(event_payload_version(Some(2)).unwrap(), 2);
+        assert!(event_payload_version(Some(0)).is_err());
+        assert!(event_payload_version(Some(LATEST_PAYLOAD_VERSION + 1))
+            .unwrap_err()
+            .contains("Unsupported payload version"));
+    }
+}
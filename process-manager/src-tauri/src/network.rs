@@ -0,0 +1,218 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::Networks;
+use tauri::{AppHandle, Manager, State};
+
+use crate::monitor::{
+    emit_payload, event_payload_version, monitor_period, MonitorKind, MonitorRegistry,
+};
+use crate::sampler::SamplerState;
+
+// Struct to contain a single network interface
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NetworkInterfaceInfo {
+    pub(crate) name: String,
+    mac_address: String,
+    // CIDR notation (e.g., "192.168.1.10/24")
+    ip_addresses: Vec<String>,
+    mtu: u64,
+    // Only carries traffic between processes on this machine
+    pub(crate) loopback: bool,
+    // Averaged over the time since the previous refresh. 0 on the first refresh
+    pub(crate) received_bytes_per_sec: u64,
+    pub(crate) transmitted_bytes_per_sec: u64,
+    received_packets_per_sec: u64,
+    transmitted_packets_per_sec: u64,
+    // Totals since the interface came up
+    received_bytes: u64,
+    transmitted_bytes: u64,
+    received_packets: u64,
+    transmitted_packets: u64,
+    receive_errors: u64,
+    transmit_errors: u64,
+    // None if the platform doesn't report dropped packets
+    receive_drops: Option<u64>,
+    transmit_drops: Option<u64>,
+}
+
+/// Reads the number of packets an interface dropped in one direction
+///
+/// `direction` is either "rx" or "tx"
+///
+/// Returns the count or `None` if the interface statistics can't be read
+#[cfg(target_os = "linux")]
+fn read_drops(name: &str, direction: &str) -> Option<u64> {
+    std::fs::read_to_string(format!(
+        "/sys/class/net/{name}/statistics/{direction}_dropped"
+    ))
+    .ok()?
+    .trim()
+    .parse()
+    .ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_drops(_name: &str, _direction: &str) -> Option<u64> {
+    None
+}
+
+/// Builds the list of network interfaces on the system
+///
+/// `networks` must already have been refreshed. `elapsed` is the time since the previous refresh,
+/// which rates are averaged over. Rates are 0 on the first refresh
+///
+/// Returns a vector of `NetworkInterfaceInfo` structs sorted by name
+pub(crate) fn get_network_interfaces(
+    networks: &Networks,
+    elapsed: Option<Duration>,
+) -> Vec<NetworkInterfaceInfo> {
+    let per_sec = |count: u64| {
+        elapsed
+            .filter(|elapsed| !elapsed.is_zero())
+            .map(|elapsed| (count as f64 / elapsed.as_secs_f64()) as u64)
+            .unwrap_or_default()
+    };
+
+    let mut interfaces = networks
+        .list()
+        .iter()
+        .map(|(name, data)| NetworkInterfaceInfo {
+            name: name.clone(),
+            mac_address: data.mac_address().to_string(),
+            ip_addresses: data
+                .ip_networks()
+                .iter()
+                .map(|network| network.to_string())
+                .collect(),
+            mtu: data.mtu(),
+            loopback: !data.ip_networks().is_empty()
+                && data
+                    .ip_networks()
+                    .iter()
+                    .all(|network| network.addr.is_loopback()),
+            received_bytes_per_sec: per_sec(data.received()),
+            transmitted_bytes_per_sec: per_sec(data.transmitted()),
+            received_packets_per_sec: per_sec(data.packets_received()),
+            transmitted_packets_per_sec: per_sec(data.packets_transmitted()),
+            received_bytes: data.total_received(),
+            transmitted_bytes: data.total_transmitted(),
+            received_packets: data.total_packets_received(),
+            transmitted_packets: data.total_packets_transmitted(),
+            receive_errors: data.total_errors_on_received(),
+            transmit_errors: data.total_errors_on_transmitted(),
+            receive_drops: read_drops(name, "rx"),
+            transmit_drops: read_drops(name, "tx"),
+        })
+        .collect::<Vec<NetworkInterfaceInfo>>();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
+
+/// Regularly updates frontend on network interface throughput
+///
+/// `app` is used to emit `network_update` events carrying a vector of `NetworkInterfaceInfo`
+/// structs. Past throughput can be queried with `query_history`
+///
+/// `interval_ms`, `adaptive` and `payload_version` work like they do for `monitor_sys_info`
+///
+/// Starting a new network monitor replaces the running one
+///
+/// Returns the ID of the new monitor or a String error if the interval or payload version is
+/// invalid
+#[tauri::command]
+pub async fn monitor_network(
+    app: AppHandle,
+    interval_ms: Option<u64>,
+    adaptive: Option<bool>,
+    payload_version: Option<u32>,
+) -> Result<u64, String> {
+    let period = monitor_period(interval_ms)?;
+    let version = event_payload_version(payload_version)?;
+    let tick_app = app.clone();
+    let tick = move || {
+        let snapshot = tick_app.state::<SamplerState>().latest();
+        let interfaces = snapshot.networks.clone();
+        // Emit the event globally and handle potential error
+        if let Err(err) = emit_payload(
+            &tick_app,
+            "network_update",
+            version,
+            snapshot.timestamp_ms,
+            interfaces,
+        ) {
+            eprintln!("Failed to emit network_update event. Error: {err}");
+        };
+    };
+
+    let registry = app.state::<Mutex<MonitorRegistry>>();
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    Ok(registry_guard.start(
+        MonitorKind::Network,
+        period,
+        adaptive.unwrap_or(false),
+        tick,
+    ))
+}
+
+/// Stops the running network monitor, if any
+///
+/// `registry` is a reference to the MonitorRegistry injected by Tauri
+///
+/// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
+#[tauri::command]
+pub fn stop_monitoring_network(registry: State<'_, Mutex<MonitorRegistry>>) -> Result<(), String> {
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    registry_guard.stop_kind(MonitorKind::Network);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, UdpSocket};
+    use std::thread;
+
+    #[test]
+    fn test_get_network_interfaces() {
+        let mut networks = Networks::new_with_refreshed_list();
+        let interfaces = get_network_interfaces(&networks, None);
+        assert!(interfaces
+            .windows(2)
+            .all(|pair| pair[0].name < pair[1].name));
+        assert!(interfaces
+            .iter()
+            .all(|interface| interface.received_bytes_per_sec == 0));
+
+        // Send some traffic over loopback
+        let receiver = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let sender = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        for _ in 0..10 {
+            sender
+                .send_to(&[0; 1000], receiver.local_addr().unwrap())
+                .unwrap();
+        }
+        thread::sleep(Duration::from_millis(100));
+        networks.refresh(true);
+
+        let interfaces = get_network_interfaces(&networks, Some(Duration::from_millis(100)));
+        let Some(loopback) = interfaces.iter().find(|interface| interface.loopback) else {
+            return; // No loopback interface is visible (e.g., in some containers)
+        };
+        assert!(loopback
+            .ip_addresses
+            .iter()
+            .any(|address| address.starts_with("127.")));
+        assert!(loopback.received_bytes_per_sec >= 10_000);
+        assert!(loopback.received_packets_per_sec >= 100);
+        assert!(loopback.received_bytes >= 10_000);
+        if cfg!(target_os = "linux") {
+            assert!(loopback.receive_drops.is_some());
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use serde::{Deserialize, Serialize};
+use sysinfo::Pid;
+
+use crate::process::parse_pid;
+
+// What a file descriptor refers to
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FileDescriptorKind {
+    File,
+    Directory,
+    Socket,
+    Pipe,
+    CharDevice,
+    BlockDevice,
+    EventFd,
+    Epoll,
+    SignalFd,
+    TimerFd,
+    Inotify,
+    // Any other anonymous inode (e.g., a pidfd or an io_uring)
+    AnonInode,
+    Other,
+}
+
+// Struct to contain a single open file descriptor
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FileDescriptor {
+    fd: u32,
+    // Path or pseudo-path (e.g., "socket:[1234]") the descriptor points to
+    target: String,
+    kind: FileDescriptorKind,
+    // File offset and open flags from fdinfo
+    position: Option<u64>,
+    flags: Option<u32>,
+    // Names of the set flags (e.g., "O_RDWR", "O_APPEND")
+    flag_names: Vec<String>,
+}
+
+// Struct to contain the open file descriptors of a process along with its limit
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OpenFiles {
+    id: String,
+    descriptors: Vec<FileDescriptor>,
+    // Limits on the number of open descriptors. `None` means unlimited or unreadable
+    soft_limit: Option<u64>,
+    hard_limit: Option<u64>,
+}
+
+// Open flags worth showing besides the access mode
+#[cfg(target_os = "linux")]
+const NAMED_FLAGS: [(libc::c_int, &str); 8] = [
+    (libc::O_CREAT, "O_CREAT"),
+    (libc::O_TRUNC, "O_TRUNC"),
+    (libc::O_APPEND, "O_APPEND"),
+    (libc::O_NONBLOCK, "O_NONBLOCK"),
+    (libc::O_DSYNC, "O_DSYNC"),
+    (libc::O_DIRECT, "O_DIRECT"),
+    (libc::O_DIRECTORY, "O_DIRECTORY"),
+    (libc::O_CLOEXEC, "O_CLOEXEC"),
+];
+
+/// Names the access mode and notable flags of a file descriptor
+///
+/// `flags` is the octal `flags` value from fdinfo
+///
+/// Returns a vector of flag names, starting with the access mode
+#[cfg(target_os = "linux")]
+fn flag_names(flags: u32) -> Vec<String> {
+    let flags = flags as libc::c_int;
+    let access_mode = match flags & libc::O_ACCMODE {
+        libc::O_RDONLY => "O_RDONLY",
+        libc::O_WRONLY => "O_WRONLY",
+        _ => "O_RDWR",
+    };
+
+    std::iter::once(access_mode)
+        .chain(
+            NAMED_FLAGS
+                .iter()
+                .filter(|(flag, _)| flags & flag != 0)
+                .map(|(_, name)| *name),
+        )
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Works out what a file descriptor refers to
+///
+/// `target` is the link target of /proc/<pid>/fd/<fd>. Pseudo-files are recognized from the target,
+/// and real paths are classified by following the link
+///
+/// Returns the `FileDescriptorKind`
+#[cfg(target_os = "linux")]
+fn classify_descriptor(link: &std::path::Path, target: &str) -> FileDescriptorKind {
+    use std::os::unix::fs::FileTypeExt;
+
+    if target.starts_with("socket:") {
+        return FileDescriptorKind::Socket;
+    }
+    if target.starts_with("pipe:") {
+        return FileDescriptorKind::Pipe;
+    }
+    if let Some(inode) = target.strip_prefix("anon_inode:") {
+        return match inode.trim_matches(|c| c == '[' || c == ']') {
+            "eventfd" => FileDescriptorKind::EventFd,
+            "eventpoll" => FileDescriptorKind::Epoll,
+            "signalfd" => FileDescriptorKind::SignalFd,
+            "timerfd" => FileDescriptorKind::TimerFd,
+            "inotify" => FileDescriptorKind::Inotify,
+            _ => FileDescriptorKind::AnonInode,
+        };
+    }
+
+    let Ok(metadata) = std::fs::metadata(link) else {
+        return FileDescriptorKind::Other;
+    };
+    let file_type = metadata.file_type();
+    if file_type.is_file() {
+        FileDescriptorKind::File
+    } else if file_type.is_dir() {
+        FileDescriptorKind::Directory
+    } else if file_type.is_fifo() {
+        FileDescriptorKind::Pipe
+    } else if file_type.is_socket() {
+        FileDescriptorKind::Socket
+    } else if file_type.is_char_device() {
+        FileDescriptorKind::CharDevice
+    } else if file_type.is_block_device() {
+        FileDescriptorKind::BlockDevice
+    } else {
+        FileDescriptorKind::Other
+    }
+}
+
+/// Reads the offset and flags of a file descriptor from /proc/<pid>/fdinfo/<fd>
+///
+/// Returns the (position, flags) pair, either of which is `None` if it couldn't be read
+#[cfg(target_os = "linux")]
+fn read_fdinfo(pid: Pid, fd: u32) -> (Option<u64>, Option<u32>) {
+    let Ok(fdinfo) = std::fs::read_to_string(format!("/proc/{pid}/fdinfo/{fd}")) else {
+        return (None, None);
+    };
+    let value = |key: &str| {
+        fdinfo
+            .lines()
+            .find_map(|line| Some(line.strip_prefix(key)?.trim().to_owned()))
+    };
+
+    (
+        value("pos:").and_then(|pos| pos.parse().ok()),
+        value("flags:").and_then(|flags| u32::from_str_radix(&flags, 8).ok()),
+    )
+}
+
+/// Reads the soft and hard limits on open files from /proc/<pid>/limits
+///
+/// Returns the (soft, hard) pair, where `None` means unlimited or unreadable
+#[cfg(target_os = "linux")]
+fn read_open_files_limit(pid: Pid) -> (Option<u64>, Option<u64>) {
+    let Ok(limits) = std::fs::read_to_string(format!("/proc/{pid}/limits")) else {
+        return (None, None);
+    };
+    let Some(line) = limits
+        .lines()
+        .find_map(|line| line.strip_prefix("Max open files"))
+    else {
+        return (None, None);
+    };
+
+    let mut values = line.split_whitespace().map(|value| value.parse().ok());
+    (values.next().flatten(), values.next().flatten())
+}
+
+/// Lists the open file descriptors of a process
+///
+/// `pid` is the Pid of the process. Descriptors that are closed while being read are skipped
+///
+/// Returns an `OpenFiles` struct or a String error if the descriptors can't be listed
+#[cfg(target_os = "linux")]
+pub(crate) fn read_open_files(pid: Pid) -> Result<OpenFiles, String> {
+    let entries = std::fs::read_dir(format!("/proc/{pid}/fd"))
+        .map_err(|e| format!("Failed to list open files of process {pid}: {e}"))?;
+
+    let mut descriptors = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let fd = entry.file_name().to_str()?.parse::<u32>().ok()?;
+            let link = entry.path();
+            let target = std::fs::read_link(&link)
+                .ok()?
+                .to_string_lossy()
+                .into_owned();
+            let (position, flags) = read_fdinfo(pid, fd);
+
+            Some(FileDescriptor {
+                fd,
+                kind: classify_descriptor(&link, &target),
+                target,
+                position,
+                flags,
+                flag_names: flags.map(flag_names).unwrap_or_default(),
+            })
+        })
+        .collect::<Vec<FileDescriptor>>();
+    descriptors.sort_by_key(|descriptor| descriptor.fd);
+
+    let (soft_limit, hard_limit) = read_open_files_limit(pid);
+    Ok(OpenFiles {
+        id: pid.to_string(),
+        descriptors,
+        soft_limit,
+        hard_limit,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn read_open_files(_pid: Pid) -> Result<OpenFiles, String> {
+    Err("Listing open files is only supported on Linux".to_owned())
+}
+
+/// Lists the open file descriptors of a process with their targets, kinds, offsets and flags
+///
+/// `id` is the Pid of the process
+///
+/// Returns an `OpenFiles` struct, including the process' open files limit, if successful and a
+/// String error if unsuccessful
+#[tauri::command]
+pub async fn list_open_files(id: &str) -> Result<OpenFiles, String> {
+    read_open_files(parse_pid(id)?)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::{Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_flag_names() {
+        // Flags that aren't worth showing are left out
+        assert_eq!(flag_names(libc::O_NOATIME as u32), vec!["O_RDONLY"]);
+        assert_eq!(
+            flag_names((libc::O_WRONLY | libc::O_APPEND | libc::O_CLOEXEC) as u32),
+            vec!["O_WRONLY", "O_APPEND", "O_CLOEXEC"]
+        );
+        assert_eq!(
+            flag_names((libc::O_RDWR | libc::O_DIRECT) as u32),
+            vec!["O_RDWR", "O_DIRECT"]
+        );
+    }
+
+    #[test]
+    fn test_list_open_files() {
+        let dir = std::env::temp_dir();
+        let read_path = dir.join(format!("open-files-read-{}", std::process::id()));
+        let append_path = dir.join(format!("open-files-append-{}", std::process::id()));
+        fs::write(&read_path, "hello").unwrap();
+
+        // Hold a file open for reading on fd 3, one for appending on fd 4 and a pipe on stdin
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(format!(
+                "exec 3<'{}' 4>>'{}' 5<'{}'; sleep 30",
+                read_path.display(),
+                append_path.display(),
+                dir.display()
+            ))
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        thread::sleep(Duration::from_millis(200));
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let open_files = rt
+            .block_on(list_open_files(&child.id().to_string()))
+            .unwrap();
+        child.kill().unwrap();
+        child.wait().unwrap();
+        fs::remove_file(&read_path).unwrap();
+        fs::remove_file(&append_path).unwrap();
+
+        let descriptor = |fd: u32| {
+            open_files
+                .descriptors
+                .iter()
+                .find(|descriptor| descriptor.fd == fd)
+                .unwrap()
+        };
+        assert_eq!(descriptor(0).kind, FileDescriptorKind::Pipe);
+
+        let read = descriptor(3);
+        assert_eq!(read.kind, FileDescriptorKind::File);
+        assert_eq!(read.target, read_path.to_string_lossy());
+        assert_eq!(read.position, Some(0));
+        assert_eq!(read.flag_names[0], "O_RDONLY");
+
+        let append = descriptor(4);
+        assert_eq!(append.target, append_path.to_string_lossy());
+        assert!(append.flag_names.contains(&"O_WRONLY".to_owned()));
+        assert!(append.flag_names.contains(&"O_APPEND".to_owned()));
+
+        assert_eq!(descriptor(5).kind, FileDescriptorKind::Directory);
+
+        let soft_limit = open_files.soft_limit.unwrap();
+        assert!(soft_limit >= open_files.descriptors.len() as u64);
+        assert!(open_files.hard_limit.is_none_or(|hard| hard >= soft_limit));
+    }
+
+    #[test]
+    fn test_list_open_files_errors() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        assert!(rt
+            .block_on(list_open_files("999999999"))
+            .unwrap_err()
+            .contains("Failed to list open files of process 999999999"));
+    }
+}
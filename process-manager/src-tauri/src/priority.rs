@@ -0,0 +1,532 @@
+use serde::{Deserialize, Serialize};
+use sysinfo::Pid;
+
+use crate::process::parse_pid;
+use crate::setting::SettingChange;
+
+// Range of nice values accepted by setpriority
+#[cfg(target_os = "linux")]
+const MIN_NICE: i32 = -20;
+#[cfg(target_os = "linux")]
+const MAX_NICE: i32 = 19;
+// Highest level of the real-time and best-effort I/O classes
+#[cfg(target_os = "linux")]
+const MAX_IO_PRIORITY_LEVEL: u8 = 7;
+
+// I/O scheduling class (see ioprio_set(2))
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum IoPriorityClass {
+    // No class set. The kernel derives a best-effort level from the nice value
+    None,
+    RealTime,
+    BestEffort,
+    Idle,
+}
+
+// Struct to contain an I/O priority. `level` goes from 0 (highest) to 7 (lowest)
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IoPriority {
+    class: IoPriorityClass,
+    level: u8,
+}
+
+/// Lists the threads a scheduling change applies to
+///
+/// Returns the IDs of every thread of the process if `all_threads` is set, otherwise only the
+/// process' main thread
+#[cfg(target_os = "linux")]
+pub(crate) fn target_threads(pid: Pid, all_threads: bool) -> Result<Vec<u32>, String> {
+    if !all_threads {
+        return Ok(vec![pid.as_u32()]);
+    }
+
+    let entries = std::fs::read_dir(format!("/proc/{pid}/task"))
+        .map_err(|e| format!("Failed to list threads of process {pid}: {e}"))?;
+    let mut tids = entries
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+        .collect::<Vec<u32>>();
+    // Change the main thread first. It is the one checked for permissions and existence, so a
+    // process that can't be changed fails before any other thread is touched
+    tids.sort_by_key(|tid| (*tid != pid.as_u32(), *tid));
+    Ok(tids)
+}
+
+/// Turns an errno from a scheduling syscall into a readable error
+///
+/// `raising` is set when the change asks for a higher priority than the thread had, which is
+/// what EACCES and EPERM usually mean for unprivileged users
+#[cfg(target_os = "linux")]
+pub(crate) fn priority_error(
+    error: std::io::Error,
+    tid: u32,
+    setting: &str,
+    raising: bool,
+) -> String {
+    match error.raw_os_error() {
+        // Only reported for the main thread, so the ID is the process ID
+        Some(libc::ESRCH) => format!("Process with ID {tid} not found."),
+        Some(libc::EACCES) | Some(libc::EPERM) if raising => format!(
+            "Raising the {setting} of thread {tid} requires elevated privileges (CAP_SYS_NICE \
+             for nice values, CAP_SYS_ADMIN for real-time I/O): {error}"
+        ),
+        Some(libc::EACCES) | Some(libc::EPERM) => format!(
+            "Not permitted to change the {setting} of thread {tid}, which belongs to another \
+             user: {error}"
+        ),
+        _ => format!("Failed to change the {setting} of thread {tid}: {error}"),
+    }
+}
+
+/// Restores the threads changed before a later thread failed
+///
+/// `set` writes a single thread's previous value. Threads that exited in the meantime count as
+/// restored
+///
+/// Returns `error`, noting which threads were restored and which kept the new value
+#[cfg(target_os = "linux")]
+pub(crate) fn roll_back<T>(
+    changes: Vec<SettingChange<T>>,
+    set: impl Fn(u32, &T) -> Result<(), std::io::Error>,
+    error: String,
+) -> String {
+    if changes.is_empty() {
+        return error;
+    }
+
+    let kept = changes
+        .into_iter()
+        .rev()
+        .filter(|change| {
+            let Ok(tid) = change.id.parse::<u32>() else {
+                return true;
+            };
+            match set(tid, &change.before) {
+                Ok(()) => false,
+                Err(error) => error.raw_os_error() != Some(libc::ESRCH),
+            }
+        })
+        .map(|change| change.id)
+        .collect::<Vec<String>>();
+    if kept.is_empty() {
+        format!("{error}. The threads changed before it were restored")
+    } else {
+        format!(
+            "{error}. Failed to restore threads {}, which keep the new value",
+            kept.join(", ")
+        )
+    }
+}
+
+/// Reads the nice value of a thread
+///
+/// Returns the nice value or the errno as an `io::Error`
+#[cfg(target_os = "linux")]
+fn get_nice(tid: u32) -> Result<i32, std::io::Error> {
+    // SAFETY: getpriority has no memory safety requirements. It can return -1 successfully, so
+    // errno is cleared first to tell the cases apart
+    unsafe {
+        *libc::__errno_location() = 0;
+        let nice = libc::getpriority(libc::PRIO_PROCESS, tid);
+        let error = std::io::Error::last_os_error();
+        if nice == -1 && error.raw_os_error() != Some(0) {
+            return Err(error);
+        }
+        Ok(nice)
+    }
+}
+
+/// Sets the nice value of a thread
+///
+/// Returns a Unit Type or the errno as an `io::Error`
+#[cfg(target_os = "linux")]
+fn set_nice(tid: u32, nice: i32) -> Result<(), std::io::Error> {
+    // SAFETY: setpriority has no memory safety requirements
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid, nice) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Changes the nice value of a process or every one of its threads
+///
+/// Linux keeps a nice value per thread, so without `all_threads` only the main thread (and
+/// threads it creates afterwards) changes. Threads that exit midway are skipped. If a thread
+/// fails, the threads changed before it are restored
+///
+/// Returns a vector of `SettingChange`s, main thread first, or a String error
+#[cfg(target_os = "linux")]
+pub(crate) fn renice(
+    pid: Pid,
+    nice: i32,
+    all_threads: bool,
+) -> Result<Vec<SettingChange<i32>>, String> {
+    if !(MIN_NICE..=MAX_NICE).contains(&nice) {
+        return Err(format!(
+            "Invalid nice value ({nice}). Expected {MIN_NICE} to {MAX_NICE}"
+        ));
+    }
+
+    let mut changes = Vec::new();
+    for tid in target_threads(pid, all_threads)? {
+        let is_main = tid == pid.as_u32();
+        let before = match get_nice(tid) {
+            Ok(before) => before,
+            Err(error) if !is_main && error.raw_os_error() == Some(libc::ESRCH) => continue,
+            Err(error) => {
+                let error = priority_error(error, tid, "nice value", false);
+                return Err(roll_back(changes, |tid, nice| set_nice(tid, *nice), error));
+            }
+        };
+
+        if let Err(error) = set_nice(tid, nice) {
+            if !is_main && error.raw_os_error() == Some(libc::ESRCH) {
+                continue;
+            }
+            let error = priority_error(error, tid, "nice value", nice < before);
+            return Err(roll_back(changes, |tid, nice| set_nice(tid, *nice), error));
+        }
+        changes.push(SettingChange {
+            id: tid.to_string(),
+            before,
+            after: get_nice(tid).unwrap_or(nice),
+        });
+    }
+    Ok(changes)
+}
+
+// ioprio_set(2) constants from include/uapi/linux/ioprio.h
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+#[cfg(target_os = "linux")]
+const IOPRIO_CLASS_SHIFT: u32 = 13;
+
+#[cfg(target_os = "linux")]
+impl IoPriority {
+    fn from_raw(ioprio: libc::c_long) -> Self {
+        let class = match ioprio >> IOPRIO_CLASS_SHIFT {
+            1 => IoPriorityClass::RealTime,
+            2 => IoPriorityClass::BestEffort,
+            3 => IoPriorityClass::Idle,
+            _ => IoPriorityClass::None,
+        };
+        IoPriority {
+            class,
+            level: (ioprio & ((1 << IOPRIO_CLASS_SHIFT) - 1)) as u8,
+        }
+    }
+
+    fn to_raw(self) -> libc::c_long {
+        let class = match self.class {
+            IoPriorityClass::None => 0,
+            IoPriorityClass::RealTime => 1,
+            IoPriorityClass::BestEffort => 2,
+            IoPriorityClass::Idle => 3,
+        };
+        (class << IOPRIO_CLASS_SHIFT) | libc::c_long::from(self.level)
+    }
+
+    /// Orders classes and levels from highest to lowest priority for permission errors
+    fn rank(self) -> (u8, u8) {
+        match self.class {
+            IoPriorityClass::RealTime => (0, self.level),
+            IoPriorityClass::BestEffort | IoPriorityClass::None => (1, self.level),
+            IoPriorityClass::Idle => (2, 0),
+        }
+    }
+}
+
+/// Reads the I/O priority of a thread
+///
+/// Returns the `IoPriority` or the errno as an `io::Error`
+#[cfg(target_os = "linux")]
+fn get_io_priority(tid: u32) -> Result<IoPriority, std::io::Error> {
+    // SAFETY: ioprio_get only takes integer arguments
+    let ioprio = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, tid) };
+    if ioprio < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(IoPriority::from_raw(ioprio))
+}
+
+/// Sets the I/O priority of a thread
+///
+/// Returns a Unit Type or the errno as an `io::Error`
+#[cfg(target_os = "linux")]
+fn set_thread_io_priority(tid: u32, priority: IoPriority) -> Result<(), std::io::Error> {
+    // SAFETY: ioprio_set only takes integer arguments
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            tid,
+            priority.to_raw(),
+        )
+    };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Changes the I/O priority of a process or every one of its threads
+///
+/// Like nice values, I/O priorities are kept per thread. Threads that exit midway are skipped. If a
+/// thread fails, the threads changed before it are restored
+///
+/// Returns a vector of `SettingChange`s, main thread first, or a String error
+#[cfg(target_os = "linux")]
+pub(crate) fn set_io_priority_of(
+    pid: Pid,
+    class: IoPriorityClass,
+    level: Option<u8>,
+    all_threads: bool,
+) -> Result<Vec<SettingChange<IoPriority>>, String> {
+    let level = match class {
+        // These classes have no levels
+        IoPriorityClass::None | IoPriorityClass::Idle => 0,
+        _ => level.unwrap_or(4),
+    };
+    if level > MAX_IO_PRIORITY_LEVEL {
+        return Err(format!(
+            "Invalid I/O priority level ({level}). Expected 0 to {MAX_IO_PRIORITY_LEVEL}"
+        ));
+    }
+    let priority = IoPriority { class, level };
+
+    let mut changes = Vec::new();
+    for tid in target_threads(pid, all_threads)? {
+        let is_main = tid == pid.as_u32();
+        let before = match get_io_priority(tid) {
+            Ok(before) => before,
+            Err(error) if !is_main && error.raw_os_error() == Some(libc::ESRCH) => continue,
+            Err(error) => {
+                let error = priority_error(error, tid, "I/O priority", false);
+                return Err(roll_back(
+                    changes,
+                    |tid, priority| set_thread_io_priority(tid, *priority),
+                    error,
+                ));
+            }
+        };
+
+        if let Err(error) = set_thread_io_priority(tid, priority) {
+            if !is_main && error.raw_os_error() == Some(libc::ESRCH) {
+                continue;
+            }
+            let raising = priority.rank() < before.rank();
+            let error = priority_error(error, tid, "I/O priority", raising);
+            return Err(roll_back(
+                changes,
+                |tid, priority| set_thread_io_priority(tid, *priority),
+                error,
+            ));
+        }
+        changes.push(SettingChange {
+            id: tid.to_string(),
+            before,
+            after: get_io_priority(tid).unwrap_or(priority),
+        });
+    }
+    Ok(changes)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn renice(
+    _pid: Pid,
+    _nice: i32,
+    _all_threads: bool,
+) -> Result<Vec<SettingChange<i32>>, String> {
+    Err("Changing process priority is only supported on Linux".to_owned())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn set_io_priority_of(
+    _pid: Pid,
+    _class: IoPriorityClass,
+    _level: Option<u8>,
+    _all_threads: bool,
+) -> Result<Vec<SettingChange<IoPriority>>, String> {
+    Err("Changing I/O priority is only supported on Linux".to_owned())
+}
+
+/// Changes the nice value of a process
+///
+/// `id` is the Pid of the process and `nice` the new value from -20 (highest priority) to 19.
+/// Lowering the value below the current one usually requires elevated privileges
+///
+/// `all_threads` applies the change to every thread instead of only the main thread
+///
+/// Returns the value of each changed thread before and after the change if successful and a String
+/// error if unsuccessful
+#[tauri::command]
+pub async fn set_process_priority(
+    id: &str,
+    nice: i32,
+    all_threads: Option<bool>,
+) -> Result<Vec<SettingChange<i32>>, String> {
+    renice(parse_pid(id)?, nice, all_threads.unwrap_or(false))
+}
+
+/// Changes the I/O scheduling class and level of a process
+///
+/// `id` is the Pid of the process. `level` goes from 0 (highest) to 7, defaults to 4 and is ignored
+/// for the `none` and `idle` classes. The real-time class requires elevated privileges
+///
+/// `all_threads` applies the change to every thread instead of only the main thread
+///
+/// Returns the priority of each changed thread before and after the change if successful and a
+/// String error if unsuccessful
+#[tauri::command]
+pub async fn set_io_priority(
+    id: &str,
+    class: IoPriorityClass,
+    level: Option<u8>,
+    all_threads: Option<bool>,
+) -> Result<Vec<SettingChange<IoPriority>>, String> {
+    set_io_priority_of(parse_pid(id)?, class, level, all_threads.unwrap_or(false))
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::process::{Child, Command};
+
+    fn spawn_sleeper() -> Child {
+        Command::new("sleep").arg("30").spawn().unwrap()
+    }
+
+    fn is_privileged() -> bool {
+        // SAFETY: geteuid has no preconditions
+        unsafe { libc::geteuid() == 0 }
+    }
+
+    #[test]
+    fn test_set_process_priority() {
+        let mut child = spawn_sleeper();
+        let id = child.id().to_string();
+        let own_nice = get_nice(std::process::id()).unwrap();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        let target = (own_nice + 5).min(MAX_NICE);
+        let changes = rt
+            .block_on(set_process_priority(&id, target, Some(true)))
+            .unwrap();
+        assert_eq!(
+            changes,
+            vec![SettingChange {
+                id: id.clone(),
+                before: own_nice,
+                after: target,
+            }]
+        );
+
+        // Undoing lowers the nice value again, which only privileged users may do
+        let undo = rt.block_on(set_process_priority(&id, own_nice, None));
+        if is_privileged() {
+            assert_eq!(undo.unwrap()[0].after, own_nice);
+        } else {
+            assert!(undo.unwrap_err().contains("requires elevated privileges"));
+        }
+
+        assert!(rt
+            .block_on(set_process_priority(&id, 20, None))
+            .unwrap_err()
+            .contains("Invalid nice value (20)"));
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_roll_back() {
+        let change = |id: &str, before: i32| SettingChange {
+            id: id.to_owned(),
+            before,
+            after: 10,
+        };
+        let restored = std::cell::RefCell::new(Vec::new());
+        let set = |tid: u32, before: &i32| {
+            restored.borrow_mut().push((tid, *before));
+            match tid {
+                2 => Err(std::io::Error::from_raw_os_error(libc::EPERM)),
+                3 => Err(std::io::Error::from_raw_os_error(libc::ESRCH)),
+                _ => Ok(()),
+            }
+        };
+
+        assert_eq!(roll_back(Vec::new(), set, "Failed".to_owned()), "Failed");
+        assert_eq!(
+            roll_back(
+                vec![change("1", 0), change("3", 0)],
+                set,
+                "Failed".to_owned()
+            ),
+            "Failed. The threads changed before it were restored"
+        );
+        assert_eq!(
+            roll_back(
+                vec![change("1", 0), change("2", 5), change("3", 0)],
+                set,
+                "Failed".to_owned()
+            ),
+            "Failed. Failed to restore threads 2, which keep the new value"
+        );
+        // Restored in reverse order, back to each thread's own previous value
+        assert_eq!(restored.borrow()[2..], [(3, 0), (2, 5), (1, 0)]);
+    }
+
+    #[test]
+    fn test_set_io_priority() {
+        let mut child = spawn_sleeper();
+        let id = child.id().to_string();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        let changes = rt
+            .block_on(set_io_priority(
+                &id,
+                IoPriorityClass::BestEffort,
+                Some(6),
+                None,
+            ))
+            .unwrap();
+        let expected = IoPriority {
+            class: IoPriorityClass::BestEffort,
+            level: 6,
+        };
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].after, expected);
+
+        let changes = rt
+            .block_on(set_io_priority(&id, IoPriorityClass::Idle, Some(3), None))
+            .unwrap();
+        assert_eq!(changes[0].before, expected);
+        assert_eq!(
+            changes[0].after,
+            IoPriority {
+                class: IoPriorityClass::Idle,
+                level: 0,
+            }
+        );
+
+        assert!(rt
+            .block_on(set_io_priority(
+                &id,
+                IoPriorityClass::BestEffort,
+                Some(8),
+                None
+            ))
+            .unwrap_err()
+            .contains("Invalid I/O priority level (8)"));
+        assert!(rt
+            .block_on(set_io_priority(
+                "999999999",
+                IoPriorityClass::Idle,
+                None,
+                None
+            ))
+            .unwrap_err()
+            .contains("Process with ID 999999999 not found."));
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
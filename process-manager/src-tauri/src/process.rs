@@ -1,25 +1,76 @@
-use std::process::{Child, Command};
+use std::collections::HashSet;
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
 use std::str::FromStr;
 use std::sync::Mutex;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
-use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, Signal, System, UpdateKind, Users};
-use tauri::{AppHandle, Emitter, Manager, State};
-use tokio::time::{interval, sleep, timeout};
+use sysinfo::{
+    Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, Signal, System, UpdateKind, Users,
+};
+use tauri::{AppHandle, Manager, State};
+use tokio::time::{sleep, timeout};
 
-use crate::MonitorUpdateState;
+use crate::lifecycle::ChildExits;
+use crate::monitor::{
+    emit_payload, event_payload_version, monitor_period, MonitorKind, MonitorRegistry,
+};
+use crate::process_delta::ProcessDeltaEncoder;
+use crate::process_query::{
+    query_processes, set_process_query, ProcessColumnCache, ProcessQuery, ProcessQueryUpdate,
+};
+use crate::process_tree::{build_process_tree, collect_descendants};
+use crate::sampler::SamplerState;
 
 // Struct to contain individual process info
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct ProcessInfo {
-    id: String,
-    name: String,
-    owner: String,
-    running_time_formatted: String,
-    memory_used: String,
-    status: String,
-    cpu_usage_percent: f32,
+    pub(crate) id: String,
+    pub(crate) parent_id: Option<String>,
+    pub(crate) name: String,
+    pub(crate) owner: String,
+    // Presentation string for `run_time_secs`
+    pub(crate) running_time_formatted: String,
+    // Presentation string for `memory_bytes`
+    pub(crate) memory_used: String,
+    pub(crate) status: String,
+    pub(crate) cpu_usage_percent: f32,
+    pub(crate) memory_bytes: u64,
+    pub(crate) run_time_secs: u64,
+    // Unix time in seconds at which the process started
+    pub(crate) start_time: u64,
+    // Storage I/O averaged over the time since the previous refresh
+    pub(crate) disk_read_bytes_per_sec: u64,
+    pub(crate) disk_written_bytes_per_sec: u64,
+    // Storage I/O since the process started
+    pub(crate) disk_read_bytes: u64,
+    pub(crate) disk_written_bytes: u64,
+    // Optional columns, only read when requested through the process query
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) pss_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) uss_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) socket_count: Option<usize>,
+    // Kept for lifecycle records. Not sent to the frontend
+    #[serde(skip)]
+    pub(crate) command_line: String,
+    // Threads show up as processes on Linux
+    #[serde(skip)]
+    pub(crate) is_thread: bool,
+}
+
+// Shape of the payload emitted by `monitor_processes`
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessListView {
+    #[default]
+    List,
+    Tree,
+    // A snapshot followed by `ProcessListEvent::Delta`s (`process_list_delta`)
+    Delta,
 }
 
 /// Formats the process runtime into a readable string
@@ -41,7 +92,7 @@ fn format_run_time(secs: u64) -> String {
 /// `bytes` is the process memory usage in bytes
 ///
 /// Returns a formatted String (e.g., "2 MB")
-fn format_memory(bytes: u64) -> String {
+pub(crate) fn format_memory(bytes: u64) -> String {
     let units = ["B", "KB", "MB", "GB", "TB", "PB", "EB"];
     let mut size = bytes as f64;
     let mut unit = 0;
@@ -59,37 +110,60 @@ fn format_memory(bytes: u64) -> String {
     }
 }
 
-/// Gets an up-to-date list of processes on the system
+/// Builds the list of processes on the system
+///
+/// `sys` must already have refreshed processes (with CPU, memory, disk usage and user) and `users`
+/// is used to resolve process owners
+///
+/// `elapsed` is the time since the previous refresh, which disk I/O rates are averaged over. Rates
+/// are 0 on the first refresh
 ///
 /// Returns a vector of `ProcessInfo` structs, one for each process
-fn get_current_processes() -> Vec<ProcessInfo> {
-    let mut sys = System::new_all();
-    let users = Users::new_with_refreshed_list();
-
-    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL); // Required for accurate CPU stats
-    sys.refresh_processes_specifics(
-        ProcessesToUpdate::All,
-        true,
-        ProcessRefreshKind::nothing()
-            .with_cpu()
-            .with_memory()
-            .with_user(UpdateKind::Always),
-    );
+pub(crate) fn get_current_processes(
+    sys: &System,
+    users: &Users,
+    elapsed: Option<Duration>,
+) -> Vec<ProcessInfo> {
+    let per_sec = |bytes: u64| {
+        elapsed
+            .filter(|elapsed| !elapsed.is_zero())
+            .map(|elapsed| (bytes as f64 / elapsed.as_secs_f64()) as u64)
+            .unwrap_or_default()
+    };
 
     let mut procs = sys
         .processes()
         .iter()
         .map(|(id, process)| ProcessInfo {
             id: id.to_string(),
+            parent_id: process.parent().map(|parent| parent.to_string()),
             name: process.name().to_string_lossy().into_owned(),
-            owner: match process.user_id() {
-                Some(user_id) => users.get_user_by_id(user_id).unwrap().name().to_owned(),
-                None => String::new(),
-            },
+            owner: process
+                .user_id()
+                .and_then(|user_id| users.get_user_by_id(user_id))
+                .map(|user| user.name().to_owned())
+                .unwrap_or_default(),
             running_time_formatted: format_run_time(process.run_time()),
             memory_used: format_memory(process.memory()),
             status: process.status().to_string(),
             cpu_usage_percent: process.cpu_usage() / sys.cpus().len() as f32,
+            memory_bytes: process.memory(),
+            run_time_secs: process.run_time(),
+            start_time: process.start_time(),
+            disk_read_bytes_per_sec: per_sec(process.disk_usage().read_bytes),
+            disk_written_bytes_per_sec: per_sec(process.disk_usage().written_bytes),
+            disk_read_bytes: process.disk_usage().total_read_bytes,
+            disk_written_bytes: process.disk_usage().total_written_bytes,
+            pss_bytes: None,
+            uss_bytes: None,
+            socket_count: None,
+            command_line: process
+                .cmd()
+                .iter()
+                .map(|arg| arg.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" "),
+            is_thread: process.thread_kind().is_some(),
         })
         .collect::<Vec<ProcessInfo>>();
 
@@ -105,53 +179,241 @@ fn get_current_processes() -> Vec<ProcessInfo> {
 /// Regularly updates frontend on all system processes
 ///
 /// `app` is used to emit event to the frontend
+///
+/// `view` selects between a flat list (`process_list_update`, the default), a parent/child tree
+/// (`process_tree_update`) and a delta-encoded list (`process_list_delta`). The delta view starts
+/// with a full snapshot every time a monitor is started
+///
+/// `query` updates the filter, sort and columns of the stored `ProcessQuery`, like
+/// `update_process_query`. In the tree view, processes whose parent was filtered out become roots.
+/// Its `columns` add optional fields (e.g., PSS and USS) to the processes that pass the filter
+///
+/// `interval_ms` is the polling interval (one second by default) and `adaptive` slows polling down
+/// while the window is hidden or minimized
+///
+/// `payload_version` selects the event shape. Version 1 (the default) emits the bare list or tree
+/// and version 2 wraps it in a `VersionedPayload`
+///
+/// Starting a new process monitor replaces the running one
+///
+/// Returns the ID of the new monitor or a String error if the query, interval or payload version
+/// is invalid
 #[tauri::command]
-pub async fn monitor_processes(app: AppHandle) {
-    // Poll for process updates every second
-    let mut interval_timer = interval(Duration::from_millis(1000));
+pub async fn monitor_processes(
+    app: AppHandle,
+    view: Option<ProcessListView>,
+    query: Option<ProcessQueryUpdate>,
+    interval_ms: Option<u64>,
+    adaptive: Option<bool>,
+    payload_version: Option<u32>,
+) -> Result<u64, String> {
+    let period = monitor_period(interval_ms)?;
+    let version = event_payload_version(payload_version)?;
+    set_process_query(
+        &app.state::<Mutex<ProcessQuery>>(),
+        query.unwrap_or_default(),
+    )?;
+    let view = view.unwrap_or_default();
+    if view == ProcessListView::Delta {
+        let encoder = app.state::<Mutex<ProcessDeltaEncoder>>();
+        let Ok(mut encoder_guard) = encoder.lock() else {
+            return Err("Failed to acquire lock on process delta encoder".to_owned());
+        };
+        encoder_guard.reset();
+    }
 
-    // NOTE: Use tauri::async_runtime::spawn() instead?
-    tokio::spawn(async move {
-        loop {
-            // Check state and exit loop if the flag is set
-            let stop_updates = {
-                let state = app.state::<Mutex<MonitorUpdateState>>();
-                let state_guard = state.lock().unwrap();
-                
-                state_guard.stop_process_updates
-            };
-            if stop_updates {
-                println!("Stopping process updates");
-                break;
+    let tick_app = app.clone();
+    let tick = move || {
+        let snapshot = tick_app.state::<SamplerState>().latest();
+        let procs = snapshot.processes.clone();
+        // Apply the latest filter and sort so query updates take effect on the next tick
+        let procs = match query_processes(
+            &tick_app.state::<Mutex<ProcessQuery>>(),
+            &tick_app.state::<Mutex<ProcessColumnCache>>(),
+            procs,
+        ) {
+            Ok(procs) => procs,
+            Err(err) => {
+                eprintln!("Failed to apply process query. Error: {err}");
+                return;
             }
-            interval_timer.tick().await;
+        };
+        // Emit the event globally and handle potential error
+        let timestamp_ms = snapshot.timestamp_ms;
+        let result = match view {
+            ProcessListView::List => emit_payload(
+                &tick_app,
+                "process_list_update",
+                version,
+                timestamp_ms,
+                procs,
+            ),
+            ProcessListView::Tree => emit_payload(
+                &tick_app,
+                "process_tree_update",
+                version,
+                timestamp_ms,
+                build_process_tree(procs),
+            ),
+            ProcessListView::Delta => {
+                let event = {
+                    let encoder = tick_app.state::<Mutex<ProcessDeltaEncoder>>();
+                    let mut encoder_guard = encoder.lock().unwrap();
 
-            let procs = get_current_processes();
-            // Emit the event globally and handle potential error
-            if let Err(err) = app.emit("process_list_update", procs) {
-                eprintln!("Failed to emit process_list_update event. Error: {err}");
-            };
-        }
-    });
+                    encoder_guard.encode(procs)
+                };
+                emit_payload(
+                    &tick_app,
+                    "process_list_delta",
+                    version,
+                    timestamp_ms,
+                    event,
+                )
+            }
+        };
+        if let Err(err) = result {
+            eprintln!("Failed to emit process update event. Error: {err}");
+        };
+    };
+
+    let registry = app.state::<Mutex<MonitorRegistry>>();
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    Ok(registry_guard.start(
+        MonitorKind::Processes,
+        period,
+        adaptive.unwrap_or(false),
+        tick,
+    ))
 }
 
-/// Updates the MonitorUpdateState to stop process updates
+/// Stops the running process monitor, if any
 ///
-/// `state` is a reference to the MonitorUpdateState injected by Tauri
+/// `registry` is a reference to the MonitorRegistry injected by Tauri
 ///
 /// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
 #[tauri::command]
 pub fn stop_monitoring_processes(
-    state: State<'_, Mutex<MonitorUpdateState>>,
+    registry: State<'_, Mutex<MonitorRegistry>>,
 ) -> Result<(), String> {
-    if let Ok(mut state_guard) = state.lock() {
-        state_guard.stop_process_updates = true;
+    if let Ok(mut registry_guard) = registry.lock() {
+        registry_guard.stop_kind(MonitorKind::Processes);
     } else {
-        return Err("Failed to acquire lock on monitoring state".to_owned());
+        return Err("Failed to acquire lock on monitor registry".to_owned());
     };
     Ok(())
 }
 
+/// Parses a process ID received from the frontend
+///
+/// `id` is the Pid of a process as a String
+///
+/// Returns the parsed `Pid` or a String error
+pub(crate) fn parse_pid(id: &str) -> Result<Pid, String> {
+    Pid::from_str(id).map_err(|e| format!("Invalid process ID ({id}) format: {e}"))
+}
+
+/// Sends a signal to a single process
+///
+/// `pid` is the Pid of the process and `signal` is the signal to send
+///
+/// Returns a Result with a Unit Type if the signal was delivered or a String error
+fn send_signal(pid: Pid, signal: Signal) -> Result<(), String> {
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+
+    let Some(process) = sys.process(pid) else {
+        return Err(format!("Process with ID {pid} not found."));
+    };
+
+    match process.kill_with(signal) {
+        Some(true) => Ok(()),
+        Some(false) => Err(format!("Failed to send {signal} signal to process {pid}.")),
+        None => Err(format!(
+            "{signal} signal is not supported on this platform."
+        )),
+    }
+}
+
+/// Polls a process until its status satisfies `predicate` or `timeout_duration` elapses
+///
+/// `predicate` receives `None` once the process no longer exists
+///
+/// Returns true if the predicate was satisfied before the timeout
+async fn wait_for_process_status<F>(pid: Pid, timeout_duration: Duration, predicate: F) -> bool
+where
+    F: Fn(Option<ProcessStatus>) -> bool,
+{
+    let wait_result = timeout(timeout_duration, async {
+        let mut local_sys = System::new();
+
+        loop {
+            local_sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+
+            if predicate(local_sys.process(pid).map(|process| process.status())) {
+                break;
+            }
+            sleep(Duration::from_millis(200)).await;
+        }
+    })
+    .await;
+
+    wait_result.is_ok()
+}
+
+/// Checks whether a process status means that the process has exited
+///
+/// Zombies count as exited since they only linger until their parent reaps them
+fn has_exited(status: Option<ProcessStatus>) -> bool {
+    matches!(
+        status,
+        None | Some(ProcessStatus::Zombie | ProcessStatus::Dead)
+    )
+}
+
+/// Polls a set of processes until all of them have exited or `timeout_duration` elapses
+///
+/// `pids` are the Pids of the processes to wait for
+///
+/// Returns the Pids of the processes that were still running when polling stopped
+async fn wait_for_processes_exit(pids: &[Pid], timeout_duration: Duration) -> HashSet<Pid> {
+    let mut local_sys = System::new();
+    let mut remaining = pids.iter().copied().collect::<HashSet<Pid>>();
+
+    let _ = timeout(timeout_duration, async {
+        loop {
+            local_sys.refresh_processes(ProcessesToUpdate::Some(pids), true);
+            remaining.retain(|pid| !has_exited(local_sys.process(*pid).map(|p| p.status())));
+
+            if remaining.is_empty() {
+                break;
+            }
+            sleep(Duration::from_millis(200)).await;
+        }
+    })
+    .await;
+
+    remaining
+}
+
+/// Gets the process group ID of a process
+///
+/// `pid` is the Pid of the process
+///
+/// Returns the process group ID or `None` if it can't be determined
+#[cfg(unix)]
+pub(crate) fn process_group_id(pid: Pid) -> Option<Pid> {
+    // SAFETY: getpgid only reads the process table and has no memory safety requirements
+    let pgid = unsafe { libc::getpgid(pid.as_u32() as libc::pid_t) };
+    (pgid >= 0).then(|| Pid::from_u32(pgid as u32))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn process_group_id(_pid: Pid) -> Option<Pid> {
+    None
+}
+
 /// Tries to kill a process gracefully using SIGTERM
 ///
 /// `id` is the Pid of the process to be terminated
@@ -159,14 +421,11 @@ pub fn stop_monitoring_processes(
 /// Returns a Result with a boolean value to indicate (un)successful signal dispatch or a String error
 #[tauri::command]
 pub async fn try_kill_process_by_id(id: &str) -> Result<bool, String> {
-    let mut sys = System::new_all();
-    sys.refresh_all();
-
     // Get Pid from id
-    let pid = match Pid::from_str(id) {
-        Ok(p) => p,
-        Err(e) => return Err(format!("Invalid process ID ({id}) format: {e}")),
-    };
+    let pid = parse_pid(id)?;
+
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
 
     // Get the process from Pid
     let Some(process) = sys.process(pid) else {
@@ -190,14 +449,11 @@ pub async fn try_kill_process_by_id(id: &str) -> Result<bool, String> {
 /// Returns a Result with a Unit Value to indicate a successful termination or a String error
 #[tauri::command]
 pub async fn force_kill_process_by_id(id: &str) -> Result<(), String> {
-    let mut sys = System::new_all();
-    sys.refresh_all();
-
     // Get Pid from id
-    let pid = match Pid::from_str(id) {
-        Ok(p) => p,
-        Err(e) => return Err(format!("Invalid process ID ({id}) format: {e}")),
-    };
+    let pid = parse_pid(id)?;
+
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
 
     // Get the process from Pid
     let Some(process) = sys.process(pid) else {
@@ -209,24 +465,11 @@ pub async fn force_kill_process_by_id(id: &str) -> Result<(), String> {
         return Err(format!("Failed to send KILL signal to process {id}."));
     }
 
-    // Timeout block: wait up to 3 secs for the process to disappear
-    let wait_result = timeout(Duration::from_secs(3), async {
-        let mut local_sys = System::new_all();
-
-        loop {
-            local_sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
-
-            if sys.process(pid).is_none() {
-                break;
-            }
-            sleep(Duration::from_millis(200)).await;
-        }
-    })
-    .await;
-
-    match wait_result {
-        Ok(_) => Ok(()),
-        Err(_) => Err(format!("Timed out waiting for process {id} to terminate.")),
+    // Wait up to 3 secs for the process to disappear
+    if wait_for_process_status(pid, Duration::from_secs(3), has_exited).await {
+        Ok(())
+    } else {
+        Err(format!("Timed out waiting for process {id} to terminate."))
     }
 
     // NOTE: Old code
@@ -273,11 +516,371 @@ pub async fn force_kill_process_by_id(id: &str) -> Result<(), String> {
     // }
 }
 
+/// Pauses a process using SIGSTOP
+///
+/// `id` is the Pid of the process to be suspended
+///
+/// Returns a Result with a Unit Type once the process is reported as stopped or a String error
+#[tauri::command]
+pub async fn suspend_process(id: &str) -> Result<(), String> {
+    let pid = parse_pid(id)?;
+    send_signal(pid, Signal::Stop)?;
+
+    // Confirm that the process actually stopped
+    if wait_for_process_status(pid, Duration::from_secs(3), |status| {
+        status == Some(ProcessStatus::Stop)
+    })
+    .await
+    {
+        Ok(())
+    } else {
+        Err(format!("Timed out waiting for process {id} to stop."))
+    }
+}
+
+/// Resumes a suspended process using SIGCONT
+///
+/// `id` is the Pid of the process to be resumed
+///
+/// Returns a Result with a Unit Type once the process is no longer reported as stopped or a String
+/// error if it timed out or the process no longer exists
+#[tauri::command]
+pub async fn resume_process(id: &str) -> Result<(), String> {
+    let pid = parse_pid(id)?;
+    send_signal(pid, Signal::Continue)?;
+
+    // Confirm that the process left the stopped state
+    if !wait_for_process_status(pid, Duration::from_secs(3), |status| {
+        status != Some(ProcessStatus::Stop)
+    })
+    .await
+    {
+        return Err(format!("Timed out waiting for process {id} to resume."));
+    }
+
+    // Leaving the stopped state also covers a process that exited in the meantime
+    let mut local_sys = System::new();
+    local_sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    if has_exited(local_sys.process(pid).map(|process| process.status())) {
+        return Err(format!("Process with ID {id} not found."));
+    }
+
+    Ok(())
+}
+
+// Everything needed to launch a process again with the same settings
+struct LaunchSpec {
+    exe: PathBuf,
+    args: Vec<OsString>,
+    cwd: PathBuf,
+    environ: Vec<(OsString, OsString)>,
+}
+
+/// Splits an environment entry into its name and value at the first `=`
+///
+/// The name must not be empty, so Windows' hidden "=C:=C:\\" entries keep their leading `=`
+///
+/// Returns the name and value or `None` if the entry has no `=`
+fn split_environ_entry(entry: &OsStr) -> Option<(OsString, OsString)> {
+    let bytes = entry.as_encoded_bytes();
+    let split = bytes.iter().skip(1).position(|byte| *byte == b'=')? + 1;
+    // SAFETY: `bytes` comes from an OsStr and is split around an ASCII character
+    let (key, value) = unsafe {
+        (
+            OsStr::from_encoded_bytes_unchecked(&bytes[..split]),
+            OsStr::from_encoded_bytes_unchecked(&bytes[split + 1..]),
+        )
+    };
+    Some((key.to_owned(), value.to_owned()))
+}
+
+/// Reads the environment of a process from /proc/<pid>/environ
+///
+/// An empty environment is valid (e.g., a process started with `env -i`), and variables that aren't
+/// valid UTF-8 are kept as they are
+///
+/// Returns the environment variables in order or a String error if the file can't be read
+#[cfg(target_os = "linux")]
+fn read_environ(pid: Pid) -> Result<Vec<(OsString, OsString)>, String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let environ = std::fs::read(format!("/proc/{pid}/environ"))
+        .map_err(|e| format!("Unable to read the environment of process {pid}: {e}"))?;
+    Ok(environ
+        .split(|byte| *byte == 0)
+        .filter_map(|entry| split_environ_entry(OsStr::from_bytes(entry)))
+        .collect())
+}
+
+/// Captures the executable, arguments, working directory and environment of a process
+///
+/// `pid` is the Pid of the process to inspect
+///
+/// Returns a `LaunchSpec` or a String error if any of the settings couldn't be read
+fn capture_launch_spec(pid: Pid) -> Result<LaunchSpec, String> {
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing()
+            .with_exe(UpdateKind::Always)
+            .with_cmd(UpdateKind::Always)
+            .with_cwd(UpdateKind::Always)
+            .with_environ(if cfg!(target_os = "linux") {
+                UpdateKind::Never
+            } else {
+                UpdateKind::Always
+            }),
+    );
+
+    let Some(process) = sys.process(pid) else {
+        return Err(format!("Process with ID {pid} not found."));
+    };
+
+    let Some(exe) = process.exe() else {
+        return Err(format!(
+            "Unable to read the executable path of process {pid}. Permission denied?"
+        ));
+    };
+    let Some(cwd) = process.cwd() else {
+        return Err(format!(
+            "Unable to read the working directory of process {pid}. Permission denied?"
+        ));
+    };
+
+    #[cfg(target_os = "linux")]
+    let environ = read_environ(pid)?;
+    // sysinfo can't tell an empty environment from one that couldn't be read on other platforms
+    #[cfg(not(target_os = "linux"))]
+    let environ = process
+        .environ()
+        .iter()
+        .filter_map(|entry| split_environ_entry(entry))
+        .collect();
+
+    Ok(LaunchSpec {
+        exe: exe.to_path_buf(),
+        args: process.cmd().iter().skip(1).cloned().collect(),
+        cwd: cwd.to_path_buf(),
+        environ,
+    })
+}
+
+/// Terminates a process and relaunches it with the same executable, arguments, working directory
+/// and environment
+///
+/// `id` is the Pid of the process to be restarted and `children` reaps the new process and records
+/// its exit status
+///
+/// Returns a Result with the Pid of the new process or a String error
+pub(crate) async fn relaunch_process(id: &str, children: &ChildExits) -> Result<String, String> {
+    let pid = parse_pid(id)?;
+    let spec = capture_launch_spec(pid)?;
+
+    // Ask nicely first and only force-kill if the process ignores SIGTERM
+    send_signal(pid, Signal::Term)?;
+    if !wait_for_process_status(pid, Duration::from_secs(3), has_exited).await {
+        send_signal(pid, Signal::Kill)?;
+        if !wait_for_process_status(pid, Duration::from_secs(3), has_exited).await {
+            return Err(format!("Timed out waiting for process {id} to terminate."));
+        }
+    }
+
+    let child = Command::new(&spec.exe)
+        .args(&spec.args)
+        .current_dir(&spec.cwd)
+        .env_clear()
+        .envs(spec.environ)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to relaunch {}: {e}", spec.exe.display()))?;
+    let new_id = child.id().to_string();
+
+    // Reap the relaunched process when it exits so it doesn't linger as a zombie
+    children.reap(child);
+
+    Ok(new_id)
+}
+
+/// Terminates a process and relaunches it with the same executable, arguments, working directory
+/// and environment
+///
+/// `id` is the Pid of the process to be restarted and `children` is a reference to the ChildExits
+/// injected by Tauri
+///
+/// Returns a Result with the Pid of the new process or a String error
+#[tauri::command]
+pub async fn restart_process(id: &str, children: State<'_, ChildExits>) -> Result<String, String> {
+    relaunch_process(id, &children).await
+}
+
+// Set of processes targeted by `kill_process_tree`
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KillScope {
+    // The process and all of its descendants
+    #[default]
+    Tree,
+    // Every process sharing the process' group ID
+    ProcessGroup,
+    // Every process sharing the process' session ID
+    Session,
+}
+
+// Struct to contain the outcome of killing a single process
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KillReport {
+    id: String,
+    name: String,
+    signal_error: Option<String>,
+    survived: bool,
+}
+
+/// Picks the processes targeted by `kill_process_tree`
+///
+/// `sys` must already have refreshed every process. The process manager itself is never a target
+///
+/// `allow_own_group` permits targeting the process group or session the process manager belongs
+/// to, which it shares with the processes it relaunched and, in a desktop session, possibly every
+/// other user process
+///
+/// Returns the Pids to signal, children before their parents, or a String error
+fn kill_targets(
+    sys: &System,
+    pid: Pid,
+    scope: KillScope,
+    allow_own_group: bool,
+) -> Result<Vec<Pid>, String> {
+    let Some(root) = sys.process(pid) else {
+        return Err(format!("Process with ID {pid} not found."));
+    };
+    let own_pid = Pid::from_u32(std::process::id());
+
+    // Order targets so that children are signalled before their parents
+    let targets = match scope {
+        KillScope::Tree => {
+            let mut descendants = collect_descendants(sys, pid);
+            descendants.reverse();
+            descendants.into_iter().map(|(pid, _)| pid).collect()
+        }
+        KillScope::ProcessGroup | KillScope::Session => {
+            let is_group = scope == KillScope::ProcessGroup;
+            let key = |process: &sysinfo::Process| {
+                if is_group {
+                    process_group_id(process.pid())
+                } else {
+                    process.session_id()
+                }
+            };
+
+            let Some(root_key) = key(root) else {
+                return Err(format!(
+                    "Unable to determine the process group or session of process {pid}."
+                ));
+            };
+            if !allow_own_group && sys.process(own_pid).and_then(key) == Some(root_key) {
+                let kind = if is_group { "process group" } else { "session" };
+                return Err(format!(
+                    "Process {pid} shares its {kind} with the process manager. Pass allowOwnGroup \
+                     to kill the rest of the {kind} anyway."
+                ));
+            }
+
+            // Children always start after their parents, so youngest first is bottom-up
+            let mut members = sys
+                .processes()
+                .values()
+                .filter(|process| key(process) == Some(root_key))
+                .collect::<Vec<&sysinfo::Process>>();
+            members.sort_by_key(|process| std::cmp::Reverse(process.start_time()));
+            members
+                .into_iter()
+                .map(|process| process.pid())
+                .collect::<Vec<Pid>>()
+        }
+    };
+    Ok(targets
+        .into_iter()
+        .filter(|target| *target != own_pid)
+        .collect())
+}
+
+/// Kills a process along with its descendants, its process group or its whole session
+///
+/// `id` is the Pid of the process to be killed
+///
+/// `scope` selects which processes are targeted, defaulting to the process tree
+///
+/// `force` sends SIGKILL instead of SIGTERM
+///
+/// `allow_own_group` must be set to target the process group or session of the process manager
+/// itself. The process manager is skipped either way
+///
+/// Returns a Result with a `KillReport` for every targeted process or a String error
+#[tauri::command]
+pub async fn kill_process_tree(
+    id: &str,
+    scope: Option<KillScope>,
+    force: Option<bool>,
+    allow_own_group: Option<bool>,
+) -> Result<Vec<KillReport>, String> {
+    let pid = parse_pid(id)?;
+    let signal = if force.unwrap_or(false) {
+        Signal::Kill
+    } else {
+        Signal::Term
+    };
+
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().without_tasks(),
+    );
+    let targets = kill_targets(
+        &sys,
+        pid,
+        scope.unwrap_or_default(),
+        allow_own_group.unwrap_or(false),
+    )?;
+
+    let mut reports = targets
+        .iter()
+        .filter_map(|pid| {
+            let process = sys.process(*pid)?;
+            let signal_error = match process.kill_with(signal) {
+                Some(true) => None,
+                Some(false) => Some(format!("Failed to send {signal} signal to process {pid}.")),
+                None => Some(format!(
+                    "{signal} signal is not supported on this platform."
+                )),
+            };
+
+            Some(KillReport {
+                id: pid.to_string(),
+                name: process.name().to_string_lossy().into_owned(),
+                signal_error,
+                survived: false,
+            })
+        })
+        .collect::<Vec<KillReport>>();
+
+    // Wait up to 3 secs for every targeted process to disappear
+    let survivors = wait_for_processes_exit(&targets, Duration::from_secs(3)).await;
+    for report in &mut reports {
+        report.survived = survivors.contains(&parse_pid(&report.id)?);
+    }
+
+    Ok(reports)
+}
+
 /// Spawns a mock process that simply sleeps for 30 seconds
 ///
 /// Returns the `Child` handler for the created process
 #[allow(dead_code)]
-fn spawn_dummy_process() -> Child {
+pub(crate) fn spawn_dummy_process() -> Child {
     Command::new("sleep")
         .arg("30")
         .spawn()
@@ -287,8 +890,8 @@ fn spawn_dummy_process() -> Child {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sampler::Sampler;
     use regex::Regex;
-    use sysinfo::ProcessStatus;
     use tokio::runtime::Runtime;
 
     #[test]
@@ -340,12 +943,17 @@ mod tests {
             ProcessStatus::Zombie.to_string(),
         ];
 
-        let procs = get_current_processes();
+        let snapshot = Sampler::new().sample();
 
-        for proc in procs {
+        for proc in &snapshot.processes {
             assert!(!proc.id.is_empty());
             assert!(proc.id.parse::<u32>().is_ok());
 
+            if let Some(parent_id) = &proc.parent_id {
+                assert!(parent_id.parse::<u32>().is_ok());
+                assert_ne!(parent_id, &proc.id);
+            }
+
             assert!(!proc.name.is_empty());
 
             assert!(!proc.running_time_formatted.is_empty());
@@ -398,4 +1006,234 @@ mod tests {
         let kill_result = rt.block_on(try_kill_process_by_id(&child_id));
         assert!(kill_result.is_ok());
     }
+
+    #[test]
+    fn test_suspend_and_resume_process() {
+        let mut child = spawn_dummy_process();
+        let child_id = child.id().to_string();
+        let pid = Pid::from_u32(child.id());
+
+        let rt = Runtime::new().unwrap();
+
+        let suspend_result = rt.block_on(suspend_process(&child_id));
+        assert!(suspend_result.is_ok());
+        let mut sys = System::new();
+        sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        assert_eq!(sys.process(pid).unwrap().status(), ProcessStatus::Stop);
+
+        let resume_result = rt.block_on(resume_process(&child_id));
+        assert!(resume_result.is_ok());
+        sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        assert_ne!(sys.process(pid).unwrap().status(), ProcessStatus::Stop);
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        // Test process ID verification
+        let verification_result = rt.block_on(suspend_process("invalid_process"));
+        assert!(verification_result
+            .unwrap_err()
+            .contains("Invalid process ID (invalid_process) format:"));
+    }
+
+    #[test]
+    fn test_split_environ_entry() {
+        assert_eq!(
+            split_environ_entry(OsStr::new("KEY=a=b")),
+            Some(("KEY".into(), "a=b".into()))
+        );
+        assert_eq!(
+            split_environ_entry(OsStr::new("=C:=C:\\")),
+            Some(("=C:".into(), "C:\\".into()))
+        );
+        assert_eq!(
+            split_environ_entry(OsStr::new("EMPTY=")),
+            Some(("EMPTY".into(), "".into()))
+        );
+        assert_eq!(split_environ_entry(OsStr::new("NO_VALUE")), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_capture_launch_spec_environ() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let value = OsStr::from_bytes(b"caf\xe9");
+        let mut with_env = Command::new("sleep")
+            .arg("30")
+            .env_clear()
+            .env("LATIN1", value)
+            .spawn()
+            .unwrap();
+        let mut without_env = Command::new("sleep").arg("30").env_clear().spawn().unwrap();
+        // Give the processes time to exec before their environment is read
+        std::thread::sleep(Duration::from_millis(200));
+
+        let spec = capture_launch_spec(Pid::from_u32(with_env.id())).unwrap();
+        assert_eq!(spec.environ, vec![("LATIN1".into(), value.to_owned())]);
+        // A process without any environment can still be relaunched
+        let spec = capture_launch_spec(Pid::from_u32(without_env.id())).unwrap();
+        assert!(spec.environ.is_empty());
+
+        for child in [&mut with_env, &mut without_env] {
+            child.kill().unwrap();
+            child.wait().unwrap();
+        }
+        assert!(capture_launch_spec(Pid::from_u32(with_env.id()))
+            .err()
+            .unwrap()
+            .contains("not found"));
+    }
+
+    #[test]
+    fn test_restart_process() {
+        let mut child = spawn_dummy_process();
+        let child_id = child.id().to_string();
+
+        let rt = Runtime::new().unwrap();
+
+        // Give the dummy process time to exec before its settings are captured
+        std::thread::sleep(Duration::from_millis(200));
+        // Reap the original process in the background so it can be seen exiting
+        let reaper = std::thread::spawn(move || child.wait());
+
+        let children = ChildExits::default();
+        let new_id = rt.block_on(relaunch_process(&child_id, &children)).unwrap();
+        assert_ne!(new_id, child_id);
+        reaper.join().unwrap().unwrap();
+
+        let new_pid = Pid::from_str(&new_id).unwrap();
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[new_pid]),
+            true,
+            ProcessRefreshKind::nothing().with_cmd(UpdateKind::Always),
+        );
+        let process = sys.process(new_pid).unwrap();
+        assert_eq!(process.name(), "sleep");
+        assert_eq!(process.cmd().last().unwrap(), "30");
+
+        assert!(process.kill());
+        // The relaunched process is reaped and its exit status recorded
+        std::thread::sleep(Duration::from_millis(500));
+        assert!(children.take(&new_id, process.start_time() + 1).is_none());
+        assert!(children.take(&new_id, process.start_time()).is_some());
+    }
+
+    /// Spawns a shell that starts two sleeping children and waits for them
+    fn spawn_dummy_process_tree(own_group: bool) -> Child {
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 30 & sleep 30 & wait"]);
+        if own_group {
+            std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        }
+        let child = command
+            .spawn()
+            .expect("Failed to spawn dummy process tree.");
+
+        // Give the shell time to start its children
+        std::thread::sleep(Duration::from_millis(300));
+        child
+    }
+
+    #[test]
+    fn test_kill_process_tree() {
+        let mut child = spawn_dummy_process_tree(false);
+        let child_id = child.id().to_string();
+
+        let rt = Runtime::new().unwrap();
+
+        let reports = rt
+            .block_on(kill_process_tree(&child_id, None, Some(true), None))
+            .unwrap();
+        child.wait().unwrap();
+
+        assert_eq!(reports.len(), 3);
+        // The shell is the root, so it must be signalled last
+        assert_eq!(reports.last().unwrap().id, child_id);
+        assert_eq!(
+            reports
+                .iter()
+                .filter(|report| report.name == "sleep")
+                .count(),
+            2
+        );
+        for report in &reports {
+            assert!(report.signal_error.is_none());
+            assert!(!report.survived);
+        }
+
+        // Test process ID verification
+        let verification_result =
+            rt.block_on(kill_process_tree("invalid_process", None, None, None));
+        assert!(verification_result
+            .unwrap_err()
+            .contains("Invalid process ID (invalid_process) format:"));
+    }
+
+    #[test]
+    fn test_kill_process_group() {
+        let mut child = spawn_dummy_process_tree(true);
+        let child_id = child.id().to_string();
+
+        let rt = Runtime::new().unwrap();
+
+        let reports = rt
+            .block_on(kill_process_tree(
+                &child_id,
+                Some(KillScope::ProcessGroup),
+                None,
+                None,
+            ))
+            .unwrap();
+        child.wait().unwrap();
+
+        assert_eq!(reports.len(), 3);
+        assert!(reports.iter().all(|report| !report.survived));
+    }
+
+    #[test]
+    fn test_kill_own_process_group() {
+        let mut child = spawn_dummy_process();
+        let child_id = child.id().to_string();
+        let rt = Runtime::new().unwrap();
+
+        // The relaunched process shares the process manager's group and session
+        std::thread::sleep(Duration::from_millis(200));
+        let reaper = std::thread::spawn(move || child.wait());
+        let children = ChildExits::default();
+        let new_id = rt.block_on(relaunch_process(&child_id, &children)).unwrap();
+        reaper.join().unwrap().unwrap();
+        let new_pid = parse_pid(&new_id).unwrap();
+
+        for scope in [KillScope::ProcessGroup, KillScope::Session] {
+            assert!(rt
+                .block_on(kill_process_tree(&new_id, Some(scope), Some(true), None))
+                .unwrap_err()
+                .contains("with the process manager"));
+        }
+
+        // Even with the override, the process manager never targets itself
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().without_tasks(),
+        );
+        let targets = kill_targets(&sys, new_pid, KillScope::ProcessGroup, true).unwrap();
+        assert!(targets.contains(&new_pid));
+        assert!(!targets.contains(&Pid::from_u32(std::process::id())));
+
+        // Only the relaunched process is killed and the test process survives
+        let reports = rt
+            .block_on(kill_process_tree(&new_id, None, Some(true), None))
+            .unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(!reports[0].survived);
+        sys.refresh_processes(
+            ProcessesToUpdate::Some(&[Pid::from_u32(std::process::id())]),
+            true,
+        );
+        assert!(sys.process(Pid::from_u32(std::process::id())).is_some());
+    }
 }
@@ -0,0 +1,502 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::process::ProcessInfo;
+use crate::process_query::{query_processes, ProcessColumnCache, ProcessQuery};
+use crate::sampler::SamplerState;
+
+// Fields of a process that changed since the previous event. Unchanged fields are omitted
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct ProcessChange {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    running_time_formatted: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_used: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cpu_usage_percent: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_time_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disk_read_bytes_per_sec: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disk_written_bytes_per_sec: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disk_read_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disk_written_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pss_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uss_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    socket_count: Option<usize>,
+}
+
+// Payload of `process_list_delta` events and the `resync` command
+//
+// Every event carries a sequence number one greater than the previous event. A gap means an event
+// was missed and the frontend should call `resync`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProcessListEvent {
+    // The full list. Replaces whatever the frontend had
+    Snapshot {
+        sequence: u64,
+        processes: Vec<ProcessInfo>,
+    },
+    // Changes since the previous event, applied in field order. `added` holds new processes and
+    // processes whose parent or start time changed (a reused PID) or that lost an optional column,
+    // which replace the old entry. `order` is only sent when the list order differs from the
+    // previous order with removed processes dropped and new ones appended
+    Delta {
+        sequence: u64,
+        removed: Vec<String>,
+        added: Vec<ProcessInfo>,
+        changed: Vec<ProcessChange>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        order: Option<Vec<String>>,
+    },
+}
+
+/// Compares the fields of a process that deltas carry
+///
+/// Returns the `ProcessChange` if any field differs
+fn diff_process(old: &ProcessInfo, new: &ProcessInfo) -> Option<ProcessChange> {
+    fn changed<T: PartialEq + Clone>(old: &T, new: &T) -> Option<T> {
+        (old != new).then(|| new.clone())
+    }
+
+    let change = ProcessChange {
+        id: new.id.clone(),
+        name: changed(&old.name, &new.name),
+        owner: changed(&old.owner, &new.owner),
+        running_time_formatted: changed(&old.running_time_formatted, &new.running_time_formatted),
+        memory_used: changed(&old.memory_used, &new.memory_used),
+        status: changed(&old.status, &new.status),
+        cpu_usage_percent: changed(&old.cpu_usage_percent, &new.cpu_usage_percent),
+        memory_bytes: changed(&old.memory_bytes, &new.memory_bytes),
+        run_time_secs: changed(&old.run_time_secs, &new.run_time_secs),
+        disk_read_bytes_per_sec: changed(
+            &old.disk_read_bytes_per_sec,
+            &new.disk_read_bytes_per_sec,
+        ),
+        disk_written_bytes_per_sec: changed(
+            &old.disk_written_bytes_per_sec,
+            &new.disk_written_bytes_per_sec,
+        ),
+        disk_read_bytes: changed(&old.disk_read_bytes, &new.disk_read_bytes),
+        disk_written_bytes: changed(&old.disk_written_bytes, &new.disk_written_bytes),
+        pss_bytes: changed(&old.pss_bytes, &new.pss_bytes).flatten(),
+        uss_bytes: changed(&old.uss_bytes, &new.uss_bytes).flatten(),
+        socket_count: changed(&old.socket_count, &new.socket_count).flatten(),
+    };
+
+    (change
+        != ProcessChange {
+            id: new.id.clone(),
+            ..Default::default()
+        })
+    .then_some(change)
+}
+
+// Remembers the last list sent to the frontend so that the next one can be sent as a delta
+#[derive(Default)]
+pub struct ProcessDeltaEncoder {
+    sequence: u64,
+    // `None` until the first snapshot has been sent, or after a reset
+    previous: Option<(Vec<String>, HashMap<String, ProcessInfo>)>,
+}
+
+impl ProcessDeltaEncoder {
+    /// Forces the next event to be a full snapshot
+    pub(crate) fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    /// Encodes a list as the next event
+    ///
+    /// `procs` is the list as it should appear in the frontend, already filtered and sorted
+    ///
+    /// Returns a snapshot if nothing has been sent since the last reset, otherwise a delta
+    pub(crate) fn encode(&mut self, procs: Vec<ProcessInfo>) -> ProcessListEvent {
+        self.sequence += 1;
+        let sequence = self.sequence;
+        let order = procs
+            .iter()
+            .map(|proc| proc.id.clone())
+            .collect::<Vec<String>>();
+
+        let Some((previous_order, previous)) = self.previous.take() else {
+            self.previous = Some((
+                order,
+                procs
+                    .iter()
+                    .map(|proc| (proc.id.clone(), proc.clone()))
+                    .collect(),
+            ));
+            return ProcessListEvent::Snapshot {
+                sequence,
+                processes: procs,
+            };
+        };
+
+        let current_ids = order.iter().collect::<HashSet<&String>>();
+        let removed = previous_order
+            .iter()
+            .filter(|id| !current_ids.contains(id))
+            .cloned()
+            .collect::<Vec<String>>();
+
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        for proc in &procs {
+            match previous.get(&proc.id) {
+                // A column that is no longer sent can't be expressed as a change
+                Some(old)
+                    if old.parent_id == proc.parent_id
+                        && old.start_time == proc.start_time
+                        && (old.pss_bytes.is_none() || proc.pss_bytes.is_some())
+                        && (old.uss_bytes.is_none() || proc.uss_bytes.is_some())
+                        && (old.socket_count.is_none() || proc.socket_count.is_some()) =>
+                {
+                    changed.extend(diff_process(old, proc));
+                }
+                _ => added.push(proc.clone()),
+            }
+        }
+
+        // The order the frontend ends up with if no order is sent
+        let expected_order = previous_order
+            .iter()
+            .filter(|id| current_ids.contains(id))
+            .chain(
+                order
+                    .iter()
+                    .filter(|id| !previous.contains_key(id.as_str())),
+            )
+            .cloned()
+            .collect::<Vec<String>>();
+        let order_changed = expected_order != order;
+
+        self.previous = Some((
+            order.clone(),
+            procs
+                .into_iter()
+                .map(|proc| (proc.id.clone(), proc))
+                .collect(),
+        ));
+        ProcessListEvent::Delta {
+            sequence,
+            removed,
+            added,
+            changed,
+            order: order_changed.then_some(order),
+        }
+    }
+}
+
+/// Builds the full, filtered and sorted process list and encodes it as a snapshot
+///
+/// Deltas emitted afterwards are relative to this snapshot
+///
+/// Returns the `ProcessListEvent::Snapshot` or a String error if a lock fails
+pub(crate) fn resync_snapshot(
+    sampler: &SamplerState,
+    query: &Mutex<ProcessQuery>,
+    column_cache: &Mutex<ProcessColumnCache>,
+    encoder: &Mutex<ProcessDeltaEncoder>,
+) -> Result<ProcessListEvent, String> {
+    let procs = sampler.latest().processes.clone();
+    let procs = query_processes(query, column_cache, procs)?;
+
+    let Ok(mut encoder_guard) = encoder.lock() else {
+        return Err("Failed to acquire lock on process delta encoder".to_owned());
+    };
+    encoder_guard.reset();
+    Ok(encoder_guard.encode(procs))
+}
+
+/// Gets a full snapshot of the process list for a frontend that missed a delta
+///
+/// The snapshot's sequence number continues the delta stream, so events with a lower or equal
+/// sequence number should be dropped. It's built off the async runtime since filling in optional
+/// columns reads files under /proc
+///
+/// Returns a `ProcessListEvent::Snapshot` if successful and a String error if unsuccessful
+#[tauri::command]
+pub async fn resync(app: AppHandle) -> Result<ProcessListEvent, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        resync_snapshot(
+            &app.state::<SamplerState>(),
+            &app.state::<Mutex<ProcessQuery>>(),
+            &app.state::<Mutex<ProcessColumnCache>>(),
+            &app.state::<Mutex<ProcessDeltaEncoder>>(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to build the process list snapshot: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampler::Sampler;
+
+    // Applies events the way the frontend does
+    #[derive(Default)]
+    struct ProcessListMirror {
+        sequence: u64,
+        processes: Vec<ProcessInfo>,
+    }
+
+    impl ProcessListMirror {
+        fn apply(&mut self, event: ProcessListEvent) -> Result<(), String> {
+            match event {
+                ProcessListEvent::Snapshot {
+                    sequence,
+                    processes,
+                } => {
+                    self.sequence = sequence;
+                    self.processes = processes;
+                }
+                ProcessListEvent::Delta {
+                    sequence,
+                    removed,
+                    added,
+                    changed,
+                    order,
+                } => {
+                    if sequence != self.sequence + 1 {
+                        return Err(format!("Gap before sequence {sequence}"));
+                    }
+                    self.sequence = sequence;
+
+                    let removed = removed.into_iter().collect::<HashSet<String>>();
+                    self.processes.retain(|proc| !removed.contains(&proc.id));
+                    for proc in added {
+                        match self.processes.iter_mut().find(|old| old.id == proc.id) {
+                            Some(old) => *old = proc,
+                            None => self.processes.push(proc),
+                        }
+                    }
+                    for change in changed {
+                        let proc = self
+                            .processes
+                            .iter_mut()
+                            .find(|proc| proc.id == change.id)
+                            .ok_or(format!("Change for unknown process {}", change.id))?;
+                        if let Some(name) = change.name {
+                            proc.name = name;
+                        }
+                        if let Some(owner) = change.owner {
+                            proc.owner = owner;
+                        }
+                        if let Some(running_time) = change.running_time_formatted {
+                            proc.running_time_formatted = running_time;
+                        }
+                        if let Some(memory_used) = change.memory_used {
+                            proc.memory_used = memory_used;
+                        }
+                        if let Some(status) = change.status {
+                            proc.status = status;
+                        }
+                        if let Some(cpu) = change.cpu_usage_percent {
+                            proc.cpu_usage_percent = cpu;
+                        }
+                        if let Some(memory_bytes) = change.memory_bytes {
+                            proc.memory_bytes = memory_bytes;
+                        }
+                        if let Some(run_time_secs) = change.run_time_secs {
+                            proc.run_time_secs = run_time_secs;
+                        }
+                        if let Some(rate) = change.disk_read_bytes_per_sec {
+                            proc.disk_read_bytes_per_sec = rate;
+                        }
+                        if let Some(rate) = change.disk_written_bytes_per_sec {
+                            proc.disk_written_bytes_per_sec = rate;
+                        }
+                        if let Some(total) = change.disk_read_bytes {
+                            proc.disk_read_bytes = total;
+                        }
+                        if let Some(total) = change.disk_written_bytes {
+                            proc.disk_written_bytes = total;
+                        }
+                        if let Some(pss_bytes) = change.pss_bytes {
+                            proc.pss_bytes = Some(pss_bytes);
+                        }
+                        if let Some(uss_bytes) = change.uss_bytes {
+                            proc.uss_bytes = Some(uss_bytes);
+                        }
+                        if let Some(socket_count) = change.socket_count {
+                            proc.socket_count = Some(socket_count);
+                        }
+                    }
+                    if let Some(order) = order {
+                        let position = order
+                            .iter()
+                            .enumerate()
+                            .map(|(index, id)| (id.as_str(), index))
+                            .collect::<HashMap<&str, usize>>();
+                        self.processes
+                            .sort_by_key(|proc| position.get(proc.id.as_str()).copied());
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn mock_process(id: u32, parent_id: Option<u32>, cpu: f32) -> ProcessInfo {
+        ProcessInfo {
+            id: id.to_string(),
+            parent_id: parent_id.map(|parent| parent.to_string()),
+            name: format!("proc-{id}"),
+            owner: "root".to_owned(),
+            running_time_formatted: String::new(),
+            memory_used: String::new(),
+            status: "Sleeping".to_owned(),
+            cpu_usage_percent: cpu,
+            memory_bytes: 1024,
+            run_time_secs: 0,
+            start_time: u64::from(id),
+            disk_read_bytes_per_sec: 0,
+            disk_written_bytes_per_sec: 0,
+            disk_read_bytes: 0,
+            disk_written_bytes: 0,
+            pss_bytes: None,
+            uss_bytes: None,
+            socket_count: None,
+            command_line: String::new(),
+            is_thread: false,
+        }
+    }
+
+    #[test]
+    fn test_encode_delta() {
+        let mut encoder = ProcessDeltaEncoder::default();
+        let mut mirror = ProcessListMirror::default();
+
+        let first = vec![
+            mock_process(1, None, 0.0),
+            mock_process(2, Some(1), 1.0),
+            mock_process(3, Some(1), 2.0),
+        ];
+        let event = encoder.encode(first.clone());
+        assert!(matches!(
+            event,
+            ProcessListEvent::Snapshot { sequence: 1, .. }
+        ));
+        mirror.apply(event).unwrap();
+
+        // 2 exits, 3 changes CPU usage, 4 starts and 1's PID is reused
+        let mut reused = mock_process(1, None, 0.0);
+        reused.start_time = 100;
+        let mut busy = mock_process(3, Some(1), 50.0);
+        busy.status = "Runnable".to_owned();
+        let second = vec![reused, busy, mock_process(4, Some(3), 0.0)];
+        let event = encoder.encode(second.clone());
+        let ProcessListEvent::Delta {
+            sequence,
+            removed,
+            added,
+            changed,
+            order,
+        } = event.clone()
+        else {
+            panic!("Expected a delta");
+        };
+        assert_eq!(sequence, 2);
+        assert_eq!(removed, vec!["2"]);
+        assert_eq!(
+            added
+                .iter()
+                .map(|proc| proc.id.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["1", "4"]
+        );
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].cpu_usage_percent, Some(50.0));
+        assert_eq!(changed[0].status.as_deref(), Some("Runnable"));
+        assert_eq!(changed[0].name, None);
+        assert_eq!(order, None);
+        mirror.apply(event).unwrap();
+        assert_eq!(mirror.processes, second);
+
+        // Only the order changes
+        let third = vec![second[2].clone(), second[0].clone(), second[1].clone()];
+        let event = encoder.encode(third.clone());
+        assert!(matches!(
+            &event,
+            ProcessListEvent::Delta { changed, order: Some(_), .. } if changed.is_empty()
+        ));
+        mirror.apply(event).unwrap();
+        assert_eq!(mirror.processes, third);
+
+        // A missed event is detected, and a reset recovers with a snapshot
+        encoder.encode(first.clone());
+        assert!(mirror.apply(encoder.encode(first.clone())).is_err());
+        encoder.reset();
+        let event = encoder.encode(first.clone());
+        assert!(matches!(
+            event,
+            ProcessListEvent::Snapshot { sequence: 6, .. }
+        ));
+        mirror.apply(event).unwrap();
+        assert_eq!(mirror.processes, first);
+    }
+
+    #[test]
+    fn test_reconstruct_real_process_list() {
+        let mut sampler = Sampler::new();
+        let mut encoder = ProcessDeltaEncoder::default();
+        let mut mirror = ProcessListMirror::default();
+
+        for _ in 0..5 {
+            let procs = sampler.sample().processes;
+            mirror.apply(encoder.encode(procs.clone())).unwrap();
+            assert_eq!(mirror.processes, procs);
+
+            // Make sure something starts or exits between samples
+            std::process::Command::new("true").status().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_resync_snapshot() {
+        let sampler = SamplerState::start(std::time::Duration::from_secs(60));
+        let query = Mutex::new(ProcessQuery::default());
+        let column_cache = Mutex::new(ProcessColumnCache::default());
+        let encoder = Mutex::new(ProcessDeltaEncoder::default());
+
+        encoder.lock().unwrap().encode(Vec::new());
+        let event = resync_snapshot(&sampler, &query, &column_cache, &encoder).unwrap();
+        let ProcessListEvent::Snapshot {
+            sequence,
+            processes,
+        } = event
+        else {
+            panic!("Expected a snapshot");
+        };
+        assert_eq!(sequence, 2);
+        assert_eq!(processes.len(), sampler.latest().processes.len());
+
+        // The stream continues from the snapshot
+        let next = encoder.lock().unwrap().encode(processes);
+        assert!(matches!(
+            next,
+            ProcessListEvent::Delta { sequence: 3, ref added, .. } if added.is_empty()
+        ));
+    }
+}
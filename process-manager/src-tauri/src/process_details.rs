@@ -0,0 +1,408 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::ops::Deref;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::limits::{read_resource_limits, ResourceLimitInfo};
+use crate::process::{parse_pid, process_group_id};
+use crate::sockets::socket_inodes;
+
+// Struct to contain a single environment variable
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct EnvVar {
+    name: String,
+    value: String,
+}
+
+// Struct to contain everything that can be read about a single process
+//
+// Fields that couldn't be read (usually because permission was denied) are `None`, and the reason
+// is stored in `errors` under the field's name
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProcessDetails {
+    id: String,
+    name: String,
+    command_line: Option<Vec<String>>,
+    exe: Option<String>,
+    cwd: Option<String>,
+    root: Option<String>,
+    environment: Option<Vec<EnvVar>>,
+    status: String,
+    // Unix time in seconds at which the process started
+    start_time: u64,
+    run_time_secs: u64,
+    parent_id: Option<String>,
+    session_id: Option<String>,
+    process_group_id: Option<String>,
+    real_user_id: Option<String>,
+    effective_user_id: Option<String>,
+    real_group_id: Option<String>,
+    effective_group_id: Option<String>,
+    thread_count: Option<usize>,
+    nice: Option<i32>,
+    priority: Option<i32>,
+    virtual_memory_bytes: u64,
+    resident_memory_bytes: u64,
+    // Total CPU time across all cores in milliseconds
+    cpu_time_ms: u64,
+    disk_read_bytes: Option<u64>,
+    disk_written_bytes: Option<u64>,
+    open_file_count: Option<usize>,
+    socket_count: Option<usize>,
+    // Every resource limit with the process' usage of it
+    resource_limits: Option<Vec<ResourceLimitInfo>>,
+    errors: BTreeMap<String, String>,
+}
+
+// Scheduling fields from /proc/<pid>/stat
+#[cfg(target_os = "linux")]
+struct ProcStat {
+    thread_count: usize,
+    nice: i32,
+    priority: i32,
+}
+
+/// Reads the scheduling fields of a process from /proc/<pid>/stat
+///
+/// Returns the `ProcStat` or a String error if the file can't be read or parsed
+#[cfg(target_os = "linux")]
+fn read_proc_stat(pid: Pid) -> Result<ProcStat, String> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).map_err(|e| e.to_string())?;
+    // The name is wrapped in parentheses and may itself contain spaces or parentheses
+    let fields = stat
+        .rsplit_once(')')
+        .map(|(_, rest)| rest.split_whitespace().collect::<Vec<&str>>())
+        .unwrap_or_default();
+    // Field N of the man page is at index N - 3 once the pid and name are removed
+    let field = |number: usize| {
+        fields
+            .get(number - 3)
+            .ok_or_else(|| format!("Missing field {number} in /proc/{pid}/stat"))
+    };
+
+    let parse_error = |e: std::num::ParseIntError| format!("Invalid /proc/{pid}/stat: {e}");
+    Ok(ProcStat {
+        priority: field(18)?.parse().map_err(parse_error)?,
+        nice: field(19)?.parse().map_err(parse_error)?,
+        thread_count: field(20)?.parse().map_err(parse_error)?,
+    })
+}
+
+/// Reads the bytes a process has read from and written to storage from /proc/<pid>/io
+///
+/// Unlike sysinfo, which reports zeros, this surfaces the error when the file is unreadable
+///
+/// Returns the (read, written) pair or a String error
+#[cfg(target_os = "linux")]
+fn read_proc_io(pid: Pid) -> Result<(u64, u64), String> {
+    let io = std::fs::read_to_string(format!("/proc/{pid}/io")).map_err(|e| e.to_string())?;
+    let value = |key: &str| {
+        io.lines()
+            .find_map(|line| line.strip_prefix(key)?.trim().parse::<u64>().ok())
+            .ok_or_else(|| format!("Missing {key} in /proc/{pid}/io"))
+    };
+    Ok((value("read_bytes:")?, value("write_bytes:")?))
+}
+
+/// Finds out why a /proc entry of a process couldn't be read
+///
+/// `entry` is the name of the file, link or directory under /proc/<pid>
+///
+/// Returns `Ok` if the entry is readable, meaning the value is genuinely empty, or the error
+#[cfg(target_os = "linux")]
+fn probe_proc_entry(pid: Pid, entry: &str) -> Result<(), String> {
+    let path = format!("/proc/{pid}/{entry}");
+    let result = match entry {
+        "exe" | "cwd" | "root" => std::fs::read_link(&path).map(|_| ()),
+        "fd" => std::fs::read_dir(&path).map(|_| ()),
+        _ => std::fs::File::open(&path).map(|_| ()),
+    };
+    result.map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn probe_proc_entry(_pid: Pid, _entry: &str) -> Result<(), String> {
+    Err("Unavailable".to_owned())
+}
+
+/// Formats a platform specific user or group ID
+fn format_id<T: Deref>(id: &T) -> String
+where
+    T::Target: Display,
+{
+    id.deref().to_string()
+}
+
+// Collects the values of a process' fields along with the reasons any were left out
+struct FieldReader {
+    pid: Pid,
+    errors: BTreeMap<String, String>,
+}
+
+impl FieldReader {
+    /// Records why a field couldn't be read
+    fn fail(&mut self, field: &str, error: String) {
+        self.errors.insert(field.to_owned(), error);
+    }
+
+    /// Keeps a value that sysinfo returns as `None` when it can't be read
+    ///
+    /// `entry` is the /proc entry used to explain a missing value
+    fn optional<T>(&mut self, field: &str, entry: &str, value: Option<T>) -> Option<T> {
+        if value.is_none() {
+            let error = probe_proc_entry(self.pid, entry)
+                .err()
+                .unwrap_or_else(|| "Unavailable".to_owned());
+            self.fail(field, error);
+        }
+        value
+    }
+
+    /// Keeps a list that sysinfo returns empty when it can't be read
+    ///
+    /// The list is only reported as missing if its /proc entry is unreadable, since many processes
+    /// (e.g., kernel threads) legitimately have an empty command line or environment
+    fn list<T>(&mut self, field: &str, entry: &str, value: Vec<T>) -> Option<Vec<T>> {
+        if !value.is_empty() {
+            return Some(value);
+        }
+        match probe_proc_entry(self.pid, entry) {
+            Ok(()) => Some(value),
+            Err(error) => {
+                self.fail(field, error);
+                None
+            }
+        }
+    }
+
+    /// Keeps the value of a fallible read
+    fn result<T>(&mut self, field: &str, value: Result<T, String>) -> Option<T> {
+        value.map_err(|error| self.fail(field, error)).ok()
+    }
+}
+
+/// Gets everything that can be read about a process
+///
+/// `sys` must have refreshed the process with `ProcessRefreshKind::everything()`. Fields that can't
+/// be read are left out and explained in `errors` rather than failing the whole call
+///
+/// Returns the `ProcessDetails` or `None` if the process isn't in `sys`
+pub(crate) fn read_process_details(sys: &System, pid: Pid) -> Option<ProcessDetails> {
+    let process = sys.process(pid)?;
+    let mut reader = FieldReader {
+        pid,
+        errors: BTreeMap::new(),
+    };
+
+    let command_line = process
+        .cmd()
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let environment = process
+        .environ()
+        .iter()
+        .filter_map(|entry| {
+            let entry = entry.to_string_lossy();
+            let (name, value) = entry.split_once('=')?;
+            Some(EnvVar {
+                name: name.to_owned(),
+                value: value.to_owned(),
+            })
+        })
+        .collect();
+    let path_string = |path: &std::path::Path| path.to_string_lossy().into_owned();
+
+    #[cfg(target_os = "linux")]
+    let (thread_count, nice, priority) = match read_proc_stat(pid) {
+        Ok(stat) => (
+            Some(stat.thread_count),
+            Some(stat.nice),
+            Some(stat.priority),
+        ),
+        Err(error) => {
+            for field in ["thread_count", "nice", "priority"] {
+                reader.fail(field, error.clone());
+            }
+            (None, None, None)
+        }
+    };
+    #[cfg(not(target_os = "linux"))]
+    let (thread_count, nice, priority) = {
+        let thread_count = process.tasks().map(|tasks| tasks.len());
+        let thread_count = reader.optional("thread_count", "task", thread_count);
+        reader.fail("nice", "Not supported on this platform".to_owned());
+        reader.fail("priority", "Not supported on this platform".to_owned());
+        (thread_count, None, None)
+    };
+
+    #[cfg(target_os = "linux")]
+    let disk_io = read_proc_io(pid);
+    #[cfg(not(target_os = "linux"))]
+    let disk_io = {
+        let usage = process.disk_usage();
+        Ok::<_, String>((usage.total_read_bytes, usage.total_written_bytes))
+    };
+    let (disk_read_bytes, disk_written_bytes) = match reader.result("disk_io", disk_io) {
+        Some((read, written)) => (Some(read), Some(written)),
+        None => (None, None),
+    };
+
+    let socket_count = socket_inodes(pid).map(|inodes| inodes.len());
+
+    Some(ProcessDetails {
+        id: pid.to_string(),
+        name: process.name().to_string_lossy().into_owned(),
+        command_line: reader.list("command_line", "cmdline", command_line),
+        exe: reader.optional("exe", "exe", process.exe().map(path_string)),
+        cwd: reader.optional("cwd", "cwd", process.cwd().map(path_string)),
+        root: reader.optional("root", "root", process.root().map(path_string)),
+        environment: reader.list("environment", "environ", environment),
+        status: process.status().to_string(),
+        start_time: process.start_time(),
+        run_time_secs: process.run_time(),
+        parent_id: process.parent().map(|parent| parent.to_string()),
+        session_id: reader.optional(
+            "session_id",
+            "stat",
+            process.session_id().map(|session| session.to_string()),
+        ),
+        process_group_id: reader.optional(
+            "process_group_id",
+            "stat",
+            process_group_id(pid).map(|group| group.to_string()),
+        ),
+        real_user_id: reader.optional("real_user_id", "status", process.user_id().map(format_id)),
+        effective_user_id: reader.optional(
+            "effective_user_id",
+            "status",
+            process.effective_user_id().map(format_id),
+        ),
+        real_group_id: reader.optional(
+            "real_group_id",
+            "status",
+            process.group_id().as_ref().map(format_id),
+        ),
+        effective_group_id: reader.optional(
+            "effective_group_id",
+            "status",
+            process.effective_group_id().as_ref().map(format_id),
+        ),
+        thread_count,
+        nice,
+        priority,
+        virtual_memory_bytes: process.virtual_memory(),
+        resident_memory_bytes: process.memory(),
+        cpu_time_ms: process.accumulated_cpu_time(),
+        disk_read_bytes,
+        disk_written_bytes,
+        open_file_count: reader.optional("open_file_count", "fd", process.open_files()),
+        socket_count: reader.result("socket_count", socket_count),
+        resource_limits: reader.result("resource_limits", read_resource_limits(pid)),
+        errors: reader.errors,
+    })
+}
+
+/// Gets detailed information about a single process
+///
+/// `id` is the Pid of the process. Fields that can't be read, usually because permission was
+/// denied, are null and explained in `errors`
+///
+/// Returns a `ProcessDetails` struct if successful and a String error if the ID is invalid or the
+/// process doesn't exist
+#[tauri::command]
+pub async fn get_process_details(id: &str) -> Result<ProcessDetails, String> {
+    let pid = parse_pid(id)?;
+
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::everything(),
+    );
+    read_process_details(&sys, pid).ok_or(format!("Process with ID {id} not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::runtime::Runtime;
+
+    #[test]
+    fn test_get_process_details() {
+        let rt = Runtime::new().unwrap();
+        let own_id = std::process::id().to_string();
+        let details = rt.block_on(get_process_details(&own_id)).unwrap();
+
+        assert_eq!(details.id, own_id);
+        assert!(!details.command_line.unwrap().is_empty());
+        assert_eq!(
+            details.exe.unwrap(),
+            std::env::current_exe().unwrap().to_string_lossy()
+        );
+        assert_eq!(
+            details.cwd.unwrap(),
+            std::env::current_dir().unwrap().to_string_lossy()
+        );
+        assert!(details
+            .environment
+            .unwrap()
+            .iter()
+            .any(|var| var.name == "PATH"));
+        assert!(details.thread_count.unwrap() >= 1);
+        assert!(details.resident_memory_bytes > 0);
+        assert!(details.virtual_memory_bytes >= details.resident_memory_bytes);
+        assert!(details.open_file_count.unwrap() >= 3);
+        assert!(details.socket_count.is_some());
+        assert!(!details.resource_limits.unwrap().is_empty());
+        assert!(details.errors.is_empty(), "{:?}", details.errors);
+    }
+
+    #[test]
+    fn test_get_process_details_errors() {
+        let rt = Runtime::new().unwrap();
+        assert!(rt
+            .block_on(get_process_details("invalid_process"))
+            .unwrap_err()
+            .contains("Invalid process ID (invalid_process) format:"));
+        assert!(rt
+            .block_on(get_process_details("999999999"))
+            .unwrap_err()
+            .contains("not found"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_field_reader() {
+        let mut reader = FieldReader {
+            pid: Pid::from_u32(u32::MAX),
+            errors: BTreeMap::new(),
+        };
+
+        assert_eq!(reader.optional("exe", "exe", Some(1)), Some(1));
+        assert_eq!(reader.optional::<u32>("cwd", "cwd", None), None);
+        assert_eq!(
+            reader.list::<u32>("environment", "environ", Vec::new()),
+            None
+        );
+        assert_eq!(reader.result("disk_io", Ok::<u32, String>(5)), Some(5));
+        assert_eq!(
+            reader.errors.keys().collect::<Vec<&String>>(),
+            vec!["cwd", "environment"]
+        );
+        assert!(reader.errors["cwd"].contains("No such file or directory"));
+
+        // An empty but readable list is not an error
+        let mut reader = FieldReader {
+            pid: Pid::from_u32(std::process::id()),
+            errors: BTreeMap::new(),
+        };
+        assert_eq!(
+            reader.list::<u32>("command_line", "cmdline", Vec::new()),
+            Some(Vec::new())
+        );
+        assert!(reader.errors.is_empty());
+    }
+}
@@ -0,0 +1,601 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::memory_map::{read_memory_usage, MemoryUsage};
+use crate::process::{parse_pid, ProcessInfo};
+use crate::sockets::socket_inodes;
+
+// Struct to contain a text predicate for a single process field
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TextMatch {
+    pattern: String,
+    // Treat `pattern` as a regular expression instead of a plain substring
+    #[serde(default)]
+    regex: bool,
+    #[serde(default)]
+    case_sensitive: bool,
+}
+
+// Struct to contain the predicates a process must satisfy to be sent to the frontend
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ProcessFilter {
+    id: Option<TextMatch>,
+    name: Option<TextMatch>,
+    owner: Option<TextMatch>,
+    // Matches any of the listed statuses (e.g., "Runnable", "Sleeping")
+    statuses: Option<Vec<String>>,
+    min_cpu_usage_percent: Option<f32>,
+    max_cpu_usage_percent: Option<f32>,
+    min_memory_bytes: Option<u64>,
+    max_memory_bytes: Option<u64>,
+}
+
+// Process field used for sorting
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    Id,
+    Name,
+    Owner,
+    Status,
+    #[default]
+    CpuUsage,
+    Memory,
+    RunningTime,
+    // Combined disk read and write rate
+    Io,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+// Struct to contain the order in which processes are sent to the frontend
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct ProcessSort {
+    field: SortField,
+    direction: SortDirection,
+}
+
+// Optional process list columns. These are costly to read, so they are only filled in on request
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessColumn {
+    // Proportional set size, from /proc/<pid>/smaps_rollup
+    Pss,
+    // Unique set size, from /proc/<pid>/smaps_rollup
+    Uss,
+    // Number of open sockets, from /proc/<pid>/fd
+    Sockets,
+}
+
+// How long a process' optional columns are reused before they are read again. Reading them walks
+// the process' page tables, which is too slow to do for every visible process on every tick
+const COLUMN_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+// Changes to the process query sent by the frontend. Fields left out keep their current value
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ProcessQueryUpdate {
+    filter: Option<ProcessFilter>,
+    sort: Option<ProcessSort>,
+    columns: Option<Vec<ProcessColumn>>,
+}
+
+// Filter, sort and optional columns applied by `monitor_processes` before emitting, with its
+// patterns pre-compiled
+#[derive(Clone, Default)]
+pub struct ProcessQuery {
+    filter: ProcessFilter,
+    sort: ProcessSort,
+    columns: Vec<ProcessColumn>,
+    id: Option<Regex>,
+    name: Option<Regex>,
+    owner: Option<Regex>,
+}
+
+/// Compiles a text predicate into a regular expression
+///
+/// Plain substrings are escaped so that both kinds of predicate can be matched the same way
+///
+/// Returns the compiled `Regex` or a String error if the pattern is invalid
+fn compile_text_match(text_match: &TextMatch) -> Result<Regex, String> {
+    let pattern = if text_match.regex {
+        text_match.pattern.clone()
+    } else {
+        regex::escape(&text_match.pattern)
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!text_match.case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid pattern ({}): {e}", text_match.pattern))
+}
+
+impl ProcessQuery {
+    /// Creates a query from a filter and sort spec
+    ///
+    /// Returns the `ProcessQuery` or a String error if any of the filter's patterns are invalid
+    pub(crate) fn new(filter: ProcessFilter, sort: ProcessSort) -> Result<Self, String> {
+        Ok(ProcessQuery {
+            id: filter.id.as_ref().map(compile_text_match).transpose()?,
+            name: filter.name.as_ref().map(compile_text_match).transpose()?,
+            owner: filter.owner.as_ref().map(compile_text_match).transpose()?,
+            filter,
+            sort,
+            columns: Vec::new(),
+        })
+    }
+
+    /// Checks a process against every predicate of the filter
+    fn matches(&self, proc: &ProcessInfo) -> bool {
+        let filter = &self.filter;
+
+        self.id.as_ref().is_none_or(|re| re.is_match(&proc.id))
+            && self.name.as_ref().is_none_or(|re| re.is_match(&proc.name))
+            && self
+                .owner
+                .as_ref()
+                .is_none_or(|re| re.is_match(&proc.owner))
+            && filter.statuses.as_ref().is_none_or(|statuses| {
+                statuses
+                    .iter()
+                    .any(|status| status.eq_ignore_ascii_case(&proc.status))
+            })
+            && filter
+                .min_cpu_usage_percent
+                .is_none_or(|min| proc.cpu_usage_percent >= min)
+            && filter
+                .max_cpu_usage_percent
+                .is_none_or(|max| proc.cpu_usage_percent <= max)
+            && filter
+                .min_memory_bytes
+                .is_none_or(|min| proc.memory_bytes >= min)
+            && filter
+                .max_memory_bytes
+                .is_none_or(|max| proc.memory_bytes <= max)
+    }
+
+    /// Compares two processes by the sort field in ascending order
+    fn compare(&self, a: &ProcessInfo, b: &ProcessInfo) -> Ordering {
+        match self.sort.field {
+            SortField::Id => {
+                a.id.parse::<u32>()
+                    .unwrap_or_default()
+                    .cmp(&b.id.parse::<u32>().unwrap_or_default())
+            }
+            SortField::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortField::Owner => a.owner.cmp(&b.owner),
+            SortField::Status => a.status.cmp(&b.status),
+            SortField::CpuUsage => a
+                .cpu_usage_percent
+                .partial_cmp(&b.cpu_usage_percent)
+                .unwrap_or(Ordering::Equal),
+            SortField::Memory => a.memory_bytes.cmp(&b.memory_bytes),
+            // Processes that started earlier have been running longer
+            SortField::RunningTime => b.start_time.cmp(&a.start_time),
+            SortField::Io => a
+                .disk_read_bytes_per_sec
+                .saturating_add(a.disk_written_bytes_per_sec)
+                .cmp(
+                    &b.disk_read_bytes_per_sec
+                        .saturating_add(b.disk_written_bytes_per_sec),
+                ),
+        }
+    }
+
+    /// Filters and sorts a list of processes
+    ///
+    /// `procs` is a list of processes as returned by `get_current_processes`
+    ///
+    /// Returns the processes that satisfy the filter, in the requested order. Optional columns are
+    /// left empty; `query_processes` fills them in
+    pub(crate) fn apply(&self, mut procs: Vec<ProcessInfo>) -> Vec<ProcessInfo> {
+        procs.retain(|proc| self.matches(proc));
+        procs.sort_by(|a, b| match self.sort.direction {
+            SortDirection::Asc => self.compare(a, b),
+            SortDirection::Desc => self.compare(b, a),
+        });
+        procs
+    }
+}
+
+// Optional column values of a single process. `None` if not requested or unreadable
+struct ColumnValues {
+    read_at: Instant,
+    memory: Option<MemoryUsage>,
+    socket_count: Option<usize>,
+}
+
+// Optional column values of the processes in the last emitted list, keyed by PID and start time so
+// that a reused PID is read again
+#[derive(Default)]
+pub struct ProcessColumnCache {
+    // Columns the entries were read for
+    columns: Vec<ProcessColumn>,
+    entries: HashMap<(u32, u64), ColumnValues>,
+}
+
+impl ProcessColumnCache {
+    /// Fills in the requested optional columns
+    ///
+    /// Values younger than `COLUMN_REFRESH_INTERVAL` are reused, unless the requested columns
+    /// changed. Entries of processes that are no longer in `procs` are dropped. Processes whose
+    /// values can't be read (e.g., another user's processes) and threads, which share their
+    /// process' memory and descriptors, are left without them
+    fn fill(&mut self, columns: &[ProcessColumn], procs: &mut [ProcessInfo]) {
+        let pss = columns.contains(&ProcessColumn::Pss);
+        let uss = columns.contains(&ProcessColumn::Uss);
+        let sockets = columns.contains(&ProcessColumn::Sockets);
+        let now = Instant::now();
+        if self.columns != columns {
+            self.columns = columns.to_vec();
+            self.entries.clear();
+        }
+
+        let mut entries = HashMap::with_capacity(procs.len());
+        for proc in procs.iter_mut().filter(|proc| !proc.is_thread) {
+            let Ok(pid) = parse_pid(&proc.id) else {
+                continue;
+            };
+            let key = (pid.as_u32(), proc.start_time);
+            let values = match self.entries.remove(&key) {
+                Some(values) if now.duration_since(values.read_at) < COLUMN_REFRESH_INTERVAL => {
+                    values
+                }
+                _ => ColumnValues {
+                    read_at: now,
+                    memory: (pss || uss).then(|| read_memory_usage(pid).ok()).flatten(),
+                    socket_count: sockets
+                        .then(|| socket_inodes(pid).ok().map(|inodes| inodes.len()))
+                        .flatten(),
+                },
+            };
+            proc.pss_bytes = values.memory.filter(|_| pss).map(|usage| usage.pss_bytes());
+            proc.uss_bytes = values.memory.filter(|_| uss).map(|usage| usage.uss_bytes());
+            proc.socket_count = values.socket_count;
+            entries.insert(key, values);
+        }
+        self.entries = entries;
+    }
+}
+
+/// Filters and sorts a list of processes with the current query and fills in its optional columns
+///
+/// The query is copied out of `query` so that `update_process_query` isn't blocked while the
+/// columns are read. Reading them goes through files under /proc, so this should be called from a
+/// blocking thread rather than an async task
+///
+/// Returns the processes to emit or a String error if a lock fails
+pub(crate) fn query_processes(
+    query: &Mutex<ProcessQuery>,
+    cache: &Mutex<ProcessColumnCache>,
+    procs: Vec<ProcessInfo>,
+) -> Result<Vec<ProcessInfo>, String> {
+    let query = {
+        let Ok(query_guard) = query.lock() else {
+            return Err("Failed to acquire lock on process query state".to_owned());
+        };
+        query_guard.clone()
+    };
+
+    let mut procs = query.apply(procs);
+    if !query.columns.is_empty() {
+        let Ok(mut cache_guard) = cache.lock() else {
+            return Err("Failed to acquire lock on process column cache".to_owned());
+        };
+        cache_guard.fill(&query.columns, &mut procs);
+    }
+    Ok(procs)
+}
+
+/// Replaces the query stored in the managed state
+///
+/// The filter, sort and columns of `update` keep their current value when `None`. Pass an empty
+/// filter or column list to clear it
+///
+/// Returns a Unit Type if successful or a String error if the lock or a pattern fails
+pub(crate) fn set_process_query(
+    state: &Mutex<ProcessQuery>,
+    update: ProcessQueryUpdate,
+) -> Result<(), String> {
+    let Ok(mut state_guard) = state.lock() else {
+        return Err("Failed to acquire lock on process query state".to_owned());
+    };
+
+    let filter = update.filter.unwrap_or_else(|| state_guard.filter.clone());
+    let sort = update.sort.unwrap_or(state_guard.sort);
+    let columns = update
+        .columns
+        .unwrap_or_else(|| state_guard.columns.clone());
+    *state_guard = ProcessQuery {
+        columns,
+        ..ProcessQuery::new(filter, sort)?
+    };
+    Ok(())
+}
+
+/// Changes the filter, sort and optional columns used by a running process monitor without
+/// restarting it
+///
+/// `state` is a reference to the ProcessQuery injected by Tauri. Fields left out of `query` keep
+/// their current value
+///
+/// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
+#[tauri::command]
+pub fn update_process_query(
+    state: State<'_, Mutex<ProcessQuery>>,
+    query: ProcessQueryUpdate,
+) -> Result<(), String> {
+    set_process_query(&state, query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_process(id: u32, name: &str, owner: &str, cpu: f32, memory: u64) -> ProcessInfo {
+        ProcessInfo {
+            id: id.to_string(),
+            parent_id: None,
+            name: name.to_owned(),
+            owner: owner.to_owned(),
+            running_time_formatted: String::new(),
+            memory_used: String::new(),
+            status: if cpu > 0.0 { "Runnable" } else { "Sleeping" }.to_owned(),
+            cpu_usage_percent: cpu,
+            memory_bytes: memory,
+            run_time_secs: 0,
+            start_time: u64::from(id),
+            disk_read_bytes_per_sec: 0,
+            disk_written_bytes_per_sec: 0,
+            disk_read_bytes: 0,
+            disk_written_bytes: 0,
+            pss_bytes: None,
+            uss_bytes: None,
+            socket_count: None,
+            command_line: String::new(),
+            is_thread: false,
+        }
+    }
+
+    fn mock_processes() -> Vec<ProcessInfo> {
+        vec![
+            mock_process(1, "systemd", "root", 0.0, 12_000),
+            mock_process(20, "firefox", "alice", 12.5, 900_000),
+            mock_process(300, "Firefox-Helper", "alice", 3.0, 200_000),
+            mock_process(4000, "bash", "bob", 0.5, 4_000),
+        ]
+    }
+
+    fn text_match(pattern: &str, regex: bool) -> Option<TextMatch> {
+        Some(TextMatch {
+            pattern: pattern.to_owned(),
+            regex,
+            case_sensitive: false,
+        })
+    }
+
+    fn ids(procs: &[ProcessInfo]) -> Vec<&str> {
+        procs.iter().map(|proc| proc.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_query() {
+        let procs = ProcessQuery::default().apply(mock_processes());
+        assert_eq!(ids(&procs), vec!["20", "300", "4000", "1"]);
+    }
+
+    #[test]
+    fn test_filter_text() {
+        let filter = ProcessFilter {
+            name: text_match("firefox", false),
+            ..Default::default()
+        };
+        let query = ProcessQuery::new(filter, ProcessSort::default()).unwrap();
+        assert_eq!(ids(&query.apply(mock_processes())), vec!["20", "300"]);
+
+        let filter = ProcessFilter {
+            name: text_match("^(bash|systemd)$", true),
+            owner: text_match("ROOT", false),
+            ..Default::default()
+        };
+        let query = ProcessQuery::new(filter, ProcessSort::default()).unwrap();
+        assert_eq!(ids(&query.apply(mock_processes())), vec!["1"]);
+
+        let filter = ProcessFilter {
+            id: text_match("^\\d{3}$", true),
+            ..Default::default()
+        };
+        let query = ProcessQuery::new(filter, ProcessSort::default()).unwrap();
+        assert_eq!(ids(&query.apply(mock_processes())), vec!["300"]);
+    }
+
+    #[test]
+    fn test_filter_thresholds_and_status() {
+        let filter = ProcessFilter {
+            min_cpu_usage_percent: Some(0.5),
+            max_memory_bytes: Some(500_000),
+            ..Default::default()
+        };
+        let query = ProcessQuery::new(filter, ProcessSort::default()).unwrap();
+        assert_eq!(ids(&query.apply(mock_processes())), vec!["300", "4000"]);
+
+        let filter = ProcessFilter {
+            statuses: Some(vec!["sleeping".to_owned()]),
+            ..Default::default()
+        };
+        let query = ProcessQuery::new(filter, ProcessSort::default()).unwrap();
+        assert_eq!(ids(&query.apply(mock_processes())), vec!["1"]);
+    }
+
+    #[test]
+    fn test_sort() {
+        let sort = ProcessSort {
+            field: SortField::Name,
+            direction: SortDirection::Asc,
+        };
+        let query = ProcessQuery::new(ProcessFilter::default(), sort).unwrap();
+        assert_eq!(
+            ids(&query.apply(mock_processes())),
+            vec!["4000", "20", "300", "1"]
+        );
+
+        let sort = ProcessSort {
+            field: SortField::Id,
+            direction: SortDirection::Desc,
+        };
+        let query = ProcessQuery::new(ProcessFilter::default(), sort).unwrap();
+        assert_eq!(
+            ids(&query.apply(mock_processes())),
+            vec!["4000", "300", "20", "1"]
+        );
+
+        let sort = ProcessSort {
+            field: SortField::RunningTime,
+            direction: SortDirection::Desc,
+        };
+        let query = ProcessQuery::new(ProcessFilter::default(), sort).unwrap();
+        assert_eq!(
+            ids(&query.apply(mock_processes())),
+            vec!["1", "20", "300", "4000"]
+        );
+
+        let mut procs = mock_processes();
+        for (proc, (read, written)) in
+            procs
+                .iter_mut()
+                .zip([(10, 0), (u64::MAX, 1), (0, 500), (15, 5)])
+        {
+            proc.disk_read_bytes_per_sec = read;
+            proc.disk_written_bytes_per_sec = written;
+        }
+        let sort = ProcessSort {
+            field: SortField::Io,
+            direction: SortDirection::Desc,
+        };
+        let query = ProcessQuery::new(ProcessFilter::default(), sort).unwrap();
+        // Totals that don't fit in a u64 saturate instead of overflowing
+        assert_eq!(ids(&query.apply(procs)), vec!["20", "300", "4000", "1"]);
+    }
+
+    #[test]
+    fn test_set_process_query() {
+        let state = Mutex::new(ProcessQuery::default());
+
+        let filter = ProcessFilter {
+            name: text_match("(unclosed", true),
+            ..Default::default()
+        };
+        let result = set_process_query(
+            &state,
+            ProcessQueryUpdate {
+                filter: Some(filter),
+                ..Default::default()
+            },
+        );
+        assert!(result.unwrap_err().contains("Invalid pattern ((unclosed):"));
+
+        let filter = ProcessFilter {
+            owner: text_match("alice", false),
+            ..Default::default()
+        };
+        set_process_query(
+            &state,
+            ProcessQueryUpdate {
+                filter: Some(filter),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let sort = ProcessSort {
+            field: SortField::Memory,
+            direction: SortDirection::Asc,
+        };
+        // Updating only the sort keeps the previous filter
+        set_process_query(
+            &state,
+            ProcessQueryUpdate {
+                sort: Some(sort),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let procs = state.lock().unwrap().apply(mock_processes());
+        assert_eq!(ids(&procs), vec!["300", "20"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_process_columns() {
+        let state = Mutex::new(ProcessQuery::default());
+        let cache = Mutex::new(ProcessColumnCache::default());
+        set_process_query(
+            &state,
+            ProcessQueryUpdate {
+                columns: Some(vec![ProcessColumn::Pss]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let procs = vec![
+            mock_process(std::process::id(), "test", "alice", 1.0, 1_000),
+            mock_process(999_999_999, "gone", "alice", 0.0, 1_000),
+        ];
+        let procs = query_processes(&state, &cache, procs).unwrap();
+        assert!(procs[0].pss_bytes.is_some_and(|pss| pss > 0));
+        assert_eq!(procs[0].uss_bytes, None);
+        assert_eq!(procs[1].pss_bytes, None);
+
+        // Fresh values are reused instead of being read again
+        let key = (std::process::id(), u64::from(std::process::id()));
+        let read_at = cache.lock().unwrap().entries[&key].read_at;
+        let procs = query_processes(&state, &cache, procs).unwrap();
+        assert!(procs[0].pss_bytes.is_some());
+        assert_eq!(cache.lock().unwrap().entries[&key].read_at, read_at);
+
+        // Requesting another column reads the values again
+        let _socket = std::net::UdpSocket::bind((std::net::Ipv4Addr::LOCALHOST, 0)).unwrap();
+        set_process_query(
+            &state,
+            ProcessQueryUpdate {
+                columns: Some(vec![ProcessColumn::Sockets]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let procs = query_processes(&state, &cache, procs).unwrap();
+        assert!(procs[0].socket_count.is_some_and(|count| count > 0));
+        assert_eq!(procs[0].pss_bytes, None);
+        assert_eq!(procs[1].socket_count, None);
+
+        // Entries of processes that left the list are dropped
+        query_processes(&state, &cache, Vec::new()).unwrap();
+        assert!(cache.lock().unwrap().entries.is_empty());
+
+        // Updating only the filter keeps the columns
+        set_process_query(
+            &state,
+            ProcessQueryUpdate {
+                filter: Some(ProcessFilter::default()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(state.lock().unwrap().columns.len(), 1);
+    }
+}
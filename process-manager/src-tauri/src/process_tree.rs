@@ -0,0 +1,301 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, System};
+
+use tauri::State;
+
+use crate::process::{format_memory, ProcessInfo};
+use crate::sampler::SamplerState;
+
+// Struct to contain a process along with its descendants
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProcessNode {
+    #[serde(flatten)]
+    process: ProcessInfo,
+    depth: usize,
+    subtree_cpu_usage_percent: f32,
+    // Presentation string for `subtree_memory_bytes`
+    subtree_memory_used: String,
+    subtree_memory_bytes: u64,
+    children: Vec<ProcessNode>,
+}
+
+/// Finds the index of each process' parent within `procs`
+///
+/// A parent is only accepted if it is present in the list and started no later than the child. A
+/// parent that started after its child means the original parent exited and its PID was reused, so
+/// the child is treated as an orphan instead
+///
+/// Returns a vector of optional parent indices, one for each process
+fn resolve_parents(procs: &[ProcessInfo]) -> Vec<Option<usize>> {
+    let index_by_id = procs
+        .iter()
+        .enumerate()
+        .map(|(index, proc)| (proc.id.as_str(), index))
+        .collect::<HashMap<&str, usize>>();
+
+    procs
+        .iter()
+        .enumerate()
+        .map(|(index, proc)| {
+            let parent_index = *index_by_id.get(proc.parent_id.as_deref()?)?;
+            let parent = &procs[parent_index];
+
+            if parent_index == index || parent.start_time > proc.start_time {
+                None
+            } else {
+                Some(parent_index)
+            }
+        })
+        .collect()
+}
+
+/// Recursively builds the node for the process at `index` along with its subtree
+///
+/// `slots` holds the processes that haven't been placed in the tree yet. A process that has
+/// already been taken is skipped, which prevents cycles from ever reaching the output
+///
+/// Returns the node, or `None` if the process was already placed
+fn build_node(
+    index: usize,
+    depth: usize,
+    children: &[Vec<usize>],
+    slots: &mut [Option<ProcessInfo>],
+) -> Option<ProcessNode> {
+    let process = slots[index].take()?;
+
+    let mut subtree_cpu_usage_percent = process.cpu_usage_percent;
+    let mut subtree_memory_bytes = process.memory_bytes;
+    let mut child_nodes = Vec::with_capacity(children[index].len());
+
+    for &child in &children[index] {
+        if let Some(node) = build_node(child, depth + 1, children, slots) {
+            subtree_cpu_usage_percent += node.subtree_cpu_usage_percent;
+            subtree_memory_bytes += node.subtree_memory_bytes;
+            child_nodes.push(node);
+        }
+    }
+
+    Some(ProcessNode {
+        process,
+        depth,
+        subtree_cpu_usage_percent,
+        subtree_memory_used: format_memory(subtree_memory_bytes),
+        subtree_memory_bytes,
+        children: child_nodes,
+    })
+}
+
+/// Arranges a flat list of processes into parent/child trees
+///
+/// `procs` is a list of processes as returned by `get_current_processes`. Siblings keep the order
+/// they had in this list. Threads are left out, since their memory and CPU time are already part
+/// of their owning process
+///
+/// Returns a vector of root `ProcessNode`s. Orphans become roots, and any cycle caused by PID reuse
+/// is broken so that every process appears exactly once
+pub(crate) fn build_process_tree(mut procs: Vec<ProcessInfo>) -> Vec<ProcessNode> {
+    procs.retain(|proc| !proc.is_thread);
+    let parents = resolve_parents(&procs);
+
+    let mut children = vec![Vec::new(); procs.len()];
+    let mut roots = Vec::new();
+    for (index, parent) in parents.iter().enumerate() {
+        match parent {
+            Some(parent_index) => children[*parent_index].push(index),
+            None => roots.push(index),
+        }
+    }
+
+    let mut slots = procs
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<Option<ProcessInfo>>>();
+    let mut tree = roots
+        .into_iter()
+        .filter_map(|root| build_node(root, 0, &children, &mut slots))
+        .collect::<Vec<ProcessNode>>();
+
+    // Anything left over is part of a parent cycle that's unreachable from a root. Promote the
+    // first remaining member of each cycle to a root
+    for index in 0..slots.len() {
+        if let Some(node) = build_node(index, 0, &children, &mut slots) {
+            tree.push(node);
+        }
+    }
+    tree
+}
+
+/// Finds a process and all of its descendants
+///
+/// `sys` must have an up-to-date process list and `root` is the Pid of the top-most process.
+/// Threads are skipped since they are covered by their owning process. Parents that started after
+/// their children are ignored, just like in `build_process_tree`
+///
+/// Returns (Pid, depth) pairs in breadth-first order, starting with `root` at depth 0
+pub(crate) fn collect_descendants(sys: &System, root: Pid) -> Vec<(Pid, usize)> {
+    let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    for (pid, process) in sys.processes() {
+        if process.thread_kind().is_some() {
+            continue;
+        }
+        let Some(parent_pid) = process.parent() else {
+            continue;
+        };
+        let Some(parent) = sys.process(parent_pid) else {
+            continue;
+        };
+
+        if parent_pid != *pid && parent.start_time() <= process.start_time() {
+            children.entry(parent_pid).or_default().push(*pid);
+        }
+    }
+
+    let mut visited = HashSet::from([root]);
+    let mut descendants = vec![(root, 0)];
+    let mut next = 0;
+    while let Some(&(pid, depth)) = descendants.get(next) {
+        for &child in children.get(&pid).into_iter().flatten() {
+            if visited.insert(child) {
+                descendants.push((child, depth + 1));
+            }
+        }
+        next += 1;
+    }
+    descendants
+}
+
+/// Gets the current processes arranged by their parent/child relationships
+///
+/// `sampler` is a reference to the SamplerState injected by Tauri
+///
+/// Returns a vector of root `ProcessNode` structs
+#[tauri::command]
+pub fn get_process_tree(sampler: State<'_, SamplerState>) -> Vec<ProcessNode> {
+    build_process_tree(sampler.latest().processes.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampler::Sampler;
+
+    fn mock_process(id: u32, parent_id: Option<u32>, start_time: u64) -> ProcessInfo {
+        ProcessInfo {
+            id: id.to_string(),
+            parent_id: parent_id.map(|parent| parent.to_string()),
+            name: format!("proc-{id}"),
+            owner: String::new(),
+            running_time_formatted: String::new(),
+            memory_used: String::new(),
+            status: String::new(),
+            cpu_usage_percent: 1.0,
+            memory_bytes: 1024,
+            run_time_secs: 0,
+            start_time,
+            disk_read_bytes_per_sec: 0,
+            disk_written_bytes_per_sec: 0,
+            disk_read_bytes: 0,
+            disk_written_bytes: 0,
+            pss_bytes: None,
+            uss_bytes: None,
+            socket_count: None,
+            command_line: String::new(),
+            is_thread: false,
+        }
+    }
+
+    fn count_nodes(nodes: &[ProcessNode]) -> usize {
+        nodes
+            .iter()
+            .map(|node| 1 + count_nodes(&node.children))
+            .sum()
+    }
+
+    #[test]
+    fn test_build_process_tree() {
+        // Threads share their process' memory and CPU time, so they must not be counted again
+        let mut thread = mock_process(5, Some(2), 10);
+        thread.is_thread = true;
+        let tree = build_process_tree(vec![
+            mock_process(1, None, 0),
+            mock_process(2, Some(1), 10),
+            mock_process(3, Some(2), 20),
+            mock_process(4, Some(1), 30),
+            thread,
+        ]);
+
+        assert_eq!(tree.len(), 1);
+        let root = &tree[0];
+        assert_eq!(root.process.id, "1");
+        assert_eq!(root.depth, 0);
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.subtree_cpu_usage_percent, 4.0);
+        assert_eq!(root.subtree_memory_used, "4 KB");
+        assert_eq!(root.subtree_memory_bytes, 4096);
+
+        let child = &root.children[0];
+        assert_eq!(child.process.id, "2");
+        assert_eq!(child.depth, 1);
+        assert_eq!(child.subtree_cpu_usage_percent, 2.0);
+        assert_eq!(child.children.len(), 1);
+        assert_eq!(child.children[0].depth, 2);
+        assert_eq!(count_nodes(&tree), 4);
+    }
+
+    #[test]
+    fn test_build_process_tree_orphans_and_pid_reuse() {
+        let tree = build_process_tree(vec![
+            mock_process(1, None, 0),
+            // Parent is no longer running
+            mock_process(2, Some(99), 10),
+            // Parent PID was reused by a process that started later
+            mock_process(3, Some(4), 20),
+            mock_process(4, Some(1), 30),
+        ]);
+
+        let root_ids = tree
+            .iter()
+            .map(|node| node.process.id.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(root_ids, vec!["1", "2", "3"]);
+        assert_eq!(count_nodes(&tree), 4);
+    }
+
+    #[test]
+    fn test_build_process_tree_breaks_cycles() {
+        // Identical start times let a reused PID slip past the start time check
+        let tree = build_process_tree(vec![
+            mock_process(1, Some(3), 0),
+            mock_process(2, Some(1), 0),
+            mock_process(3, Some(2), 0),
+            mock_process(4, Some(4), 0),
+        ]);
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(count_nodes(&tree), 4);
+    }
+
+    #[test]
+    fn test_collect_descendants() {
+        let mut sys = System::new();
+        sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+
+        let own_pid = sysinfo::get_current_pid().unwrap();
+        let descendants = collect_descendants(&sys, own_pid);
+        assert_eq!(descendants[0], (own_pid, 0));
+        assert!(descendants[1..].iter().all(|&(_, depth)| depth > 0));
+    }
+
+    #[test]
+    fn test_get_process_tree() {
+        let tree = build_process_tree(Sampler::new().sample().processes);
+
+        assert!(!tree.is_empty());
+        for root in &tree {
+            assert_eq!(root.depth, 0);
+            assert!(root.subtree_cpu_usage_percent >= root.process.cpu_usage_percent);
+        }
+    }
+}
@@ -0,0 +1,880 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::history::TimeRange;
+use crate::process::ProcessInfo;
+use crate::sampler::{unix_time_ms, SamplerState, Snapshot};
+
+const DATABASE_FILE_NAME: &str = "metrics.sqlite3";
+// Write at least every 10 snapshots or every 10 seconds, whichever comes first
+const BATCH_SIZE: usize = 10;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+// Retention is enforced after this many flushes rather than after every one
+const PRUNE_EVERY: u64 = 30;
+const DEFAULT_QUERY_LIMIT: u32 = 10_000;
+
+// Schema changes, applied in order. `PRAGMA user_version` holds the number that have been applied
+const MIGRATIONS: &[&str] = &[
+    // 1: Sessions with system and per-process samples
+    "CREATE TABLE sessions (
+        id INTEGER PRIMARY KEY,
+        started_at_ms INTEGER NOT NULL,
+        ended_at_ms INTEGER
+    );
+    CREATE TABLE system_samples (
+        session_id INTEGER NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+        timestamp_ms INTEGER NOT NULL,
+        cpu_usage_percent REAL NOT NULL,
+        total_memory_bytes INTEGER NOT NULL,
+        used_memory_bytes INTEGER NOT NULL
+    );
+    CREATE INDEX system_samples_by_time ON system_samples (timestamp_ms);
+    CREATE TABLE process_samples (
+        session_id INTEGER NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+        timestamp_ms INTEGER NOT NULL,
+        pid INTEGER NOT NULL,
+        parent_pid INTEGER,
+        name TEXT NOT NULL,
+        owner TEXT NOT NULL,
+        status TEXT NOT NULL,
+        cpu_usage_percent REAL NOT NULL,
+        memory_bytes INTEGER NOT NULL,
+        start_time INTEGER NOT NULL
+    );
+    CREATE INDEX process_samples_by_time ON process_samples (timestamp_ms);
+    CREATE INDEX process_samples_by_pid ON process_samples (pid, timestamp_ms);",
+];
+
+// Struct to contain how a recording samples and how long it keeps data
+//
+// The defaults write at most 21 rows (one system and 20 process samples) every 5 seconds, which
+// is about 40 MB a day including indexes. Recording every process instead writes one row per
+// process per sample, which on a typical desktop adds up to several GB a week
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct RecorderOptions {
+    // Minimum time between recorded snapshots. Snapshots in between are skipped
+    sample_interval_ms: u64,
+    // Samples older than this are deleted while recording
+    retention_ms: u64,
+    // Only record processes using at least this much CPU. Records every process when 0
+    min_cpu_usage_percent: f32,
+    // Only record this many of the busiest processes per snapshot. No cap when 0
+    max_processes_per_sample: usize,
+}
+
+impl Default for RecorderOptions {
+    fn default() -> Self {
+        RecorderOptions {
+            sample_interval_ms: 5000,
+            retention_ms: 24 * 60 * 60 * 1000,
+            min_cpu_usage_percent: 1.0,
+            max_processes_per_sample: 20,
+        }
+    }
+}
+
+// Struct to contain a recording session and how much it recorded
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecordingSession {
+    id: i64,
+    started_at_ms: u64,
+    // `None` while recording or if the app exited without stopping the recorder
+    ended_at_ms: Option<u64>,
+    system_samples: u64,
+    process_samples: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecordedSystemSample {
+    session_id: i64,
+    timestamp_ms: u64,
+    cpu_usage_percent: f32,
+    total_memory_bytes: u64,
+    used_memory_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecordedProcessSample {
+    session_id: i64,
+    timestamp_ms: u64,
+    id: String,
+    parent_id: Option<String>,
+    name: String,
+    owner: String,
+    status: String,
+    cpu_usage_percent: f32,
+    memory_bytes: u64,
+    start_time: u64,
+}
+
+// Struct to contain how much data a prune removed
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug)]
+pub struct PruneReport {
+    sessions: u64,
+    system_samples: u64,
+    process_samples: u64,
+}
+
+// A recording in progress
+struct ActiveRecording {
+    session_id: i64,
+    token: CancellationToken,
+    writer: JoinHandle<()>,
+}
+
+// Managed state holding the recording in progress, if any
+#[derive(Default)]
+pub struct RecorderState {
+    active: Option<ActiveRecording>,
+}
+
+/// Opens the recording database and brings its schema up to date
+///
+/// Returns the `Connection` or a String error if the database can't be opened or migrated
+pub(crate) fn open_database(path: &Path) -> Result<Connection, String> {
+    let mut conn = Connection::open(path)
+        .map_err(|e| format!("Failed to open database ({}): {e}", path.display()))?;
+    conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")
+        .map_err(|e| format!("Failed to configure database: {e}"))?;
+    migrate(&mut conn)?;
+    Ok(conn)
+}
+
+/// Applies every migration the database hasn't seen yet, each in its own transaction
+///
+/// Returns a Unit Type if successful or a String error if a migration fails or the database was
+/// created by a newer version of the app
+fn migrate(conn: &mut Connection) -> Result<(), String> {
+    let version: usize = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read schema version: {e}"))?;
+    if version > MIGRATIONS.len() {
+        return Err(format!(
+            "Database schema version ({version}) is newer than this app supports ({})",
+            MIGRATIONS.len()
+        ));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start migration: {e}"))?;
+        tx.execute_batch(migration)
+            .and_then(|_| tx.pragma_update(None, "user_version", index + 1))
+            .and_then(|_| tx.commit())
+            .map_err(|e| format!("Failed to apply migration {}: {e}", index + 1))?;
+    }
+    Ok(())
+}
+
+/// Gets the path of the recording database in the app data dir, creating the dir if needed
+///
+/// Returns the `PathBuf` or a String error if the app data dir is unavailable
+fn database_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {e}"))?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create app data dir ({}): {e}", dir.display()))?;
+    Ok(dir.join(DATABASE_FILE_NAME))
+}
+
+/// Writes a batch of snapshots to a session in a single transaction
+///
+/// Returns a Unit Type if successful or a String error if any write fails
+fn write_batch(
+    conn: &mut Connection,
+    session_id: i64,
+    batch: &[Arc<Snapshot>],
+    options: &RecorderOptions,
+) -> Result<(), String> {
+    let write = |conn: &mut Connection| -> rusqlite::Result<()> {
+        let tx = conn.transaction()?;
+        {
+            let mut insert_system = tx.prepare_cached(
+                "INSERT INTO system_samples
+                 (session_id, timestamp_ms, cpu_usage_percent, total_memory_bytes, used_memory_bytes)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            let mut insert_process = tx.prepare_cached(
+                "INSERT INTO process_samples
+                 (session_id, timestamp_ms, pid, parent_pid, name, owner, status, cpu_usage_percent,
+                  memory_bytes, start_time)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+
+            for snapshot in batch {
+                let system = &snapshot.system;
+                insert_system.execute(params![
+                    session_id,
+                    snapshot.timestamp_ms,
+                    system.cpu_usage_percent,
+                    system.total_memory_bytes,
+                    system.used_memory_bytes,
+                ])?;
+
+                let mut procs = snapshot
+                    .processes
+                    .iter()
+                    // Threads share their process' CPU time and memory
+                    .filter(|proc| {
+                        !proc.is_thread && proc.cpu_usage_percent >= options.min_cpu_usage_percent
+                    })
+                    .collect::<Vec<&ProcessInfo>>();
+                if options.max_processes_per_sample > 0 {
+                    procs.sort_by(|a, b| b.cpu_usage_percent.total_cmp(&a.cpu_usage_percent));
+                    procs.truncate(options.max_processes_per_sample);
+                }
+
+                for proc in procs {
+                    insert_process.execute(params![
+                        session_id,
+                        snapshot.timestamp_ms,
+                        proc.id.parse::<u32>().unwrap_or_default(),
+                        proc.parent_id
+                            .as_ref()
+                            .and_then(|parent_id| parent_id.parse::<u32>().ok()),
+                        proc.name,
+                        proc.owner,
+                        proc.status,
+                        proc.cpu_usage_percent,
+                        proc.memory_bytes,
+                        proc.start_time,
+                    ])?;
+                }
+            }
+        }
+        tx.commit()
+    };
+
+    write(conn).map_err(|e| format!("Failed to write recorded samples: {e}"))
+}
+
+/// Deletes samples older than `before_ms` along with sessions that no longer have any samples
+///
+/// Sessions that are still recording are kept even if all of their samples were removed
+///
+/// Returns a `PruneReport` or a String error if the delete fails
+pub(crate) fn prune(conn: &mut Connection, before_ms: u64) -> Result<PruneReport, String> {
+    let delete = |conn: &mut Connection| -> rusqlite::Result<PruneReport> {
+        let tx = conn.transaction()?;
+        let system_samples = tx.execute(
+            "DELETE FROM system_samples WHERE timestamp_ms < ?1",
+            [before_ms],
+        )?;
+        let process_samples = tx.execute(
+            "DELETE FROM process_samples WHERE timestamp_ms < ?1",
+            [before_ms],
+        )?;
+        let sessions = tx.execute(
+            "DELETE FROM sessions
+             WHERE ended_at_ms IS NOT NULL
+               AND NOT EXISTS (SELECT 1 FROM system_samples WHERE session_id = sessions.id)
+               AND NOT EXISTS (SELECT 1 FROM process_samples WHERE session_id = sessions.id)",
+            [],
+        )?;
+        tx.commit()?;
+
+        Ok(PruneReport {
+            sessions: sessions as u64,
+            system_samples: system_samples as u64,
+            process_samples: process_samples as u64,
+        })
+    };
+
+    delete(conn).map_err(|e| format!("Failed to prune recordings: {e}"))
+}
+
+/// Writes snapshots to the database until `snapshots` disconnects, then ends the session
+///
+/// Snapshots are batched by count and time. Write errors are logged and the batch is dropped so
+/// that a transient failure doesn't stop the recording
+fn run_writer(
+    mut conn: Connection,
+    session_id: i64,
+    snapshots: mpsc::Receiver<Arc<Snapshot>>,
+    options: RecorderOptions,
+) {
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    let mut flushes: u64 = 0;
+    let mut flush = |conn: &mut Connection, batch: &mut Vec<Arc<Snapshot>>| {
+        if batch.is_empty() {
+            return;
+        }
+        if let Err(err) = write_batch(conn, session_id, batch, &options) {
+            eprintln!("{err}");
+        }
+        batch.clear();
+
+        flushes += 1;
+        if flushes.is_multiple_of(PRUNE_EVERY) {
+            let before_ms = unix_time_ms().saturating_sub(options.retention_ms);
+            if let Err(err) = prune(conn, before_ms) {
+                eprintln!("{err}");
+            }
+        }
+    };
+
+    loop {
+        match snapshots.recv_timeout(FLUSH_INTERVAL) {
+            Ok(snapshot) => {
+                batch.push(snapshot);
+                if batch.len() >= BATCH_SIZE {
+                    flush(&mut conn, &mut batch);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => flush(&mut conn, &mut batch),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    flush(&mut conn, &mut batch);
+    if let Err(err) = conn.execute(
+        "UPDATE sessions SET ended_at_ms = ?1 WHERE id = ?2",
+        params![unix_time_ms(), session_id],
+    ) {
+        eprintln!("Failed to end recording session {session_id}. Error: {err}");
+    }
+}
+
+/// Forwards snapshots to the writer thread, skipping any taken sooner than the sample interval
+async fn forward_snapshots(
+    mut snapshots: watch::Receiver<Arc<Snapshot>>,
+    writer: mpsc::Sender<Arc<Snapshot>>,
+    token: CancellationToken,
+    sample_interval_ms: u64,
+) {
+    let mut last_recorded_ms: Option<u64> = None;
+    loop {
+        let snapshot = snapshots.borrow_and_update().clone();
+        if last_recorded_ms.is_none_or(|last_ms| {
+            snapshot.timestamp_ms >= last_ms.saturating_add(sample_interval_ms)
+        }) {
+            last_recorded_ms = Some(snapshot.timestamp_ms);
+            if writer.send(snapshot).is_err() {
+                break;
+            }
+        }
+
+        tokio::select! {
+            _ = token.cancelled() => break,
+            changed = snapshots.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl ActiveRecording {
+    /// Waits for the writer to write its last batch and end the session
+    ///
+    /// Blocks, so it must not run on the main thread or while holding the recorder lock
+    ///
+    /// Returns the id of the session
+    fn wait(self) -> i64 {
+        if self.writer.join().is_err() {
+            eprintln!("Recording writer for session {} panicked", self.session_id);
+        }
+        self.session_id
+    }
+}
+
+impl RecorderState {
+    /// Starts recording snapshots to a new session
+    ///
+    /// A recording still in progress is stopped and its writer left to finish in the background
+    ///
+    /// Returns the new session's id or a String error if the database can't be prepared
+    pub(crate) fn start(
+        &mut self,
+        path: &Path,
+        snapshots: watch::Receiver<Arc<Snapshot>>,
+        options: RecorderOptions,
+    ) -> Result<i64, String> {
+        self.take();
+
+        let conn = open_database(path)?;
+        conn.execute(
+            "INSERT INTO sessions (started_at_ms) VALUES (?1)",
+            [unix_time_ms()],
+        )
+        .map_err(|e| format!("Failed to create recording session: {e}"))?;
+        let session_id = conn.last_insert_rowid();
+
+        let (sender, receiver) = mpsc::channel();
+        let writer = thread::spawn(move || run_writer(conn, session_id, receiver, options));
+        let token = CancellationToken::new();
+        tauri::async_runtime::spawn(forward_snapshots(
+            snapshots,
+            sender,
+            token.clone(),
+            options.sample_interval_ms,
+        ));
+
+        self.active = Some(ActiveRecording {
+            session_id,
+            token,
+            writer,
+        });
+        Ok(session_id)
+    }
+
+    /// Stops the recording in progress without waiting for its writer
+    ///
+    /// Returns the stopped recording, if any, so that its writer can be waited on elsewhere
+    fn take(&mut self) -> Option<ActiveRecording> {
+        let recording = self.active.take()?;
+        // The writer exits once the forwarding task drops its sender
+        recording.token.cancel();
+        Some(recording)
+    }
+}
+
+/// Stops the recording in progress and waits for its last batch to be written
+///
+/// The writer is joined on a blocking thread after the recorder lock is released, so neither the
+/// main thread nor other recorder commands wait on the flush
+///
+/// Returns the id of the stopped session, if any, or a String error
+async fn finish_recording(recorder: &Mutex<RecorderState>) -> Result<Option<i64>, String> {
+    let recording = {
+        let Ok(mut recorder_guard) = recorder.lock() else {
+            return Err("Failed to acquire lock on recorder state".to_owned());
+        };
+        recorder_guard.take()
+    };
+    let Some(recording) = recording else {
+        return Ok(None);
+    };
+
+    tauri::async_runtime::spawn_blocking(move || recording.wait())
+        .await
+        .map(Some)
+        .map_err(|e| format!("Failed to wait for the recording writer: {e}"))
+}
+
+/// Gets every recording session, newest first
+///
+/// Returns a vector of `RecordingSession` structs or a String error if the query fails
+pub(crate) fn list_sessions(conn: &Connection) -> Result<Vec<RecordingSession>, String> {
+    let query = || -> rusqlite::Result<Vec<RecordingSession>> {
+        let mut statement = conn.prepare(
+            "SELECT id, started_at_ms, ended_at_ms,
+                    (SELECT COUNT(*) FROM system_samples WHERE session_id = sessions.id),
+                    (SELECT COUNT(*) FROM process_samples WHERE session_id = sessions.id)
+             FROM sessions ORDER BY started_at_ms DESC, id DESC",
+        )?;
+        let sessions = statement.query_map([], |row| {
+            Ok(RecordingSession {
+                id: row.get(0)?,
+                started_at_ms: row.get(1)?,
+                ended_at_ms: row.get(2)?,
+                system_samples: row.get(3)?,
+                process_samples: row.get(4)?,
+            })
+        })?;
+        sessions.collect()
+    };
+
+    query().map_err(|e| format!("Failed to list recording sessions: {e}"))
+}
+
+/// Gets the recorded system samples within a time range, oldest first
+///
+/// `session_id` optionally limits the results to a single session. `resolution_ms` optionally
+/// averages the samples of each session into buckets of that length, each reported at the start
+/// of its bucket. At most `limit` samples are returned, 10,000 by default
+///
+/// Returns a vector of `RecordedSystemSample` structs or a String error if the query fails
+pub(crate) fn query_system_samples(
+    conn: &Connection,
+    range: TimeRange,
+    session_id: Option<i64>,
+    resolution_ms: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<RecordedSystemSample>, String> {
+    let query = || -> rusqlite::Result<Vec<RecordedSystemSample>> {
+        let mut statement = conn.prepare(
+            "SELECT session_id, timestamp_ms / ?5 * ?5 AS bucket_ms, AVG(cpu_usage_percent),
+                    MAX(total_memory_bytes), CAST(AVG(used_memory_bytes) AS INTEGER)
+             FROM system_samples
+             WHERE timestamp_ms >= ?1 AND timestamp_ms <= ?2
+               AND (?3 IS NULL OR session_id = ?3)
+             GROUP BY session_id, bucket_ms
+             ORDER BY bucket_ms, session_id
+             LIMIT ?4",
+        )?;
+        let samples = statement.query_map(
+            params![
+                range.start_ms,
+                range.end_ms.unwrap_or(i64::MAX as u64),
+                session_id,
+                limit.unwrap_or(DEFAULT_QUERY_LIMIT),
+                // Buckets of 1 ms keep every sample
+                resolution_ms.unwrap_or(1).max(1),
+            ],
+            |row| {
+                Ok(RecordedSystemSample {
+                    session_id: row.get(0)?,
+                    timestamp_ms: row.get(1)?,
+                    cpu_usage_percent: row.get(2)?,
+                    total_memory_bytes: row.get(3)?,
+                    used_memory_bytes: row.get(4)?,
+                })
+            },
+        )?;
+        samples.collect()
+    };
+
+    query().map_err(|e| format!("Failed to query recorded system samples: {e}"))
+}
+
+/// Gets the recorded process samples within a time range, oldest first
+///
+/// `session_id` and `pid` optionally narrow the results. At most `limit` samples are returned,
+/// 10,000 by default
+///
+/// Returns a vector of `RecordedProcessSample` structs or a String error if the query fails
+pub(crate) fn query_process_samples(
+    conn: &Connection,
+    range: TimeRange,
+    session_id: Option<i64>,
+    pid: Option<u32>,
+    limit: Option<u32>,
+) -> Result<Vec<RecordedProcessSample>, String> {
+    let query = || -> rusqlite::Result<Vec<RecordedProcessSample>> {
+        let mut statement = conn.prepare(
+            "SELECT session_id, timestamp_ms, pid, parent_pid, name, owner, status,
+                    cpu_usage_percent, memory_bytes, start_time
+             FROM process_samples
+             WHERE timestamp_ms >= ?1 AND timestamp_ms <= ?2
+               AND (?3 IS NULL OR session_id = ?3)
+               AND (?4 IS NULL OR pid = ?4)
+             ORDER BY timestamp_ms, pid
+             LIMIT ?5",
+        )?;
+        let samples = statement.query_map(
+            params![
+                range.start_ms,
+                range.end_ms.unwrap_or(i64::MAX as u64),
+                session_id,
+                pid,
+                limit.unwrap_or(DEFAULT_QUERY_LIMIT),
+            ],
+            |row| {
+                Ok(RecordedProcessSample {
+                    session_id: row.get(0)?,
+                    timestamp_ms: row.get(1)?,
+                    id: row.get::<_, u32>(2)?.to_string(),
+                    parent_id: row
+                        .get::<_, Option<u32>>(3)?
+                        .map(|parent| parent.to_string()),
+                    name: row.get(4)?,
+                    owner: row.get(5)?,
+                    status: row.get(6)?,
+                    cpu_usage_percent: row.get(7)?,
+                    memory_bytes: row.get(8)?,
+                    start_time: row.get(9)?,
+                })
+            },
+        )?;
+        samples.collect()
+    };
+
+    query().map_err(|e| format!("Failed to query recorded process samples: {e}"))
+}
+
+/// Starts recording system and process samples to the database in the app data dir
+///
+/// `options` controls the sample interval, retention and which processes are recorded. Starting a
+/// recording while one is in progress ends the old session
+///
+/// Returns the id of the new session if successful and a String error if unsuccessful
+#[tauri::command]
+pub async fn start_recording(
+    app: AppHandle,
+    options: Option<RecorderOptions>,
+) -> Result<i64, String> {
+    let path = database_path(&app)?;
+    let snapshots = app.state::<SamplerState>().subscribe();
+
+    let recorder = app.state::<Mutex<RecorderState>>();
+    finish_recording(&recorder).await?;
+    let Ok(mut recorder_guard) = recorder.lock() else {
+        return Err("Failed to acquire lock on recorder state".to_owned());
+    };
+    recorder_guard.start(&path, snapshots, options.unwrap_or_default())
+}
+
+/// Stops the recording in progress, if any
+///
+/// `recorder` is a reference to the RecorderState injected by Tauri
+///
+/// Returns the id of the stopped session (null in JavaScript if nothing was recording) if
+/// successful and a String error if unsuccessful
+#[tauri::command]
+pub async fn stop_recording(
+    recorder: State<'_, Mutex<RecorderState>>,
+) -> Result<Option<i64>, String> {
+    finish_recording(&recorder).await
+}
+
+/// Opens the database in the app data dir and runs `work` on it off the async runtime
+///
+/// Opening the database, scanning ranges and deleting samples can all take a while
+///
+/// Returns the result of `work` or a String error if the database can't be opened
+async fn with_database<T, F>(app: &AppHandle, work: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Connection) -> Result<T, String> + Send + 'static,
+{
+    let path = database_path(app)?;
+    tauri::async_runtime::spawn_blocking(move || work(&mut open_database(&path)?))
+        .await
+        .map_err(|e| format!("Failed to access the recording database: {e}"))?
+}
+
+/// Gets every recording session, newest first
+///
+/// Returns a vector of `RecordingSession` structs if successful and a String error if unsuccessful
+#[tauri::command]
+pub async fn list_recording_sessions(app: AppHandle) -> Result<Vec<RecordingSession>, String> {
+    with_database(&app, |conn| list_sessions(conn)).await
+}
+
+/// Gets the recorded system samples within a time range
+///
+/// `session_id` optionally limits the results to a single session, `resolution_ms` averages the
+/// samples into buckets of that length and `limit` caps how many are returned
+///
+/// Returns a vector of `RecordedSystemSample` structs if successful and a String error if
+/// unsuccessful
+#[tauri::command]
+pub async fn query_recorded_system(
+    app: AppHandle,
+    range: TimeRange,
+    session_id: Option<i64>,
+    resolution_ms: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<RecordedSystemSample>, String> {
+    with_database(&app, move |conn| {
+        query_system_samples(conn, range, session_id, resolution_ms, limit)
+    })
+    .await
+}
+
+/// Gets the recorded process samples within a time range
+///
+/// `session_id` and `pid` optionally narrow the results and `limit` caps how many are returned
+///
+/// Returns a vector of `RecordedProcessSample` structs if successful and a String error if
+/// unsuccessful
+#[tauri::command]
+pub async fn query_recorded_processes(
+    app: AppHandle,
+    range: TimeRange,
+    session_id: Option<i64>,
+    pid: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<RecordedProcessSample>, String> {
+    let pid = pid
+        .map(|pid| {
+            pid.parse::<u32>()
+                .map_err(|e| format!("Invalid process ID ({pid}) format: {e}"))
+        })
+        .transpose()?;
+    with_database(&app, move |conn| {
+        query_process_samples(conn, range, session_id, pid, limit)
+    })
+    .await
+}
+
+/// Deletes recorded samples taken before `before_ms` and any finished sessions left empty
+///
+/// Returns a `PruneReport` if successful and a String error if unsuccessful
+#[tauri::command]
+pub async fn prune_recordings(app: AppHandle, before_ms: u64) -> Result<PruneReport, String> {
+    with_database(&app, move |conn| prune(conn, before_ms)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampler::Sampler;
+
+    fn temp_database_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("recorder-{name}-{}.sqlite3", std::process::id()));
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+        path
+    }
+
+    fn snapshot_at(timestamp_ms: u64) -> Arc<Snapshot> {
+        let mut snapshot = Sampler::new().sample();
+        snapshot.timestamp_ms = timestamp_ms;
+        Arc::new(snapshot)
+    }
+
+    #[test]
+    fn test_migrations() {
+        let path = temp_database_path("migrations");
+        let conn = open_database(&path).unwrap();
+        let version: usize = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        drop(conn);
+
+        // Reopening an up-to-date database is a no-op
+        open_database(&path).unwrap();
+
+        // A database from a newer version of the app is rejected
+        Connection::open(&path)
+            .unwrap()
+            .pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+            .unwrap();
+        assert!(open_database(&path).unwrap_err().contains("newer"));
+    }
+
+    #[test]
+    fn test_write_query_and_prune() {
+        let path = temp_database_path("write");
+        let mut conn = open_database(&path).unwrap();
+        conn.execute(
+            "INSERT INTO sessions (started_at_ms, ended_at_ms) VALUES (0, 5000)",
+            [],
+        )
+        .unwrap();
+        let session_id = conn.last_insert_rowid();
+
+        let batch = vec![snapshot_at(1000), snapshot_at(2000), snapshot_at(3000)];
+        let process_count = batch
+            .iter()
+            .flat_map(|snapshot| &snapshot.processes)
+            .filter(|proc| !proc.is_thread)
+            .count();
+        // Record every process
+        let options = RecorderOptions {
+            min_cpu_usage_percent: 0.0,
+            max_processes_per_sample: 0,
+            ..Default::default()
+        };
+        write_batch(&mut conn, session_id, &batch, &options).unwrap();
+
+        let sessions = list_sessions(&conn).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].system_samples, 3);
+        assert_eq!(sessions[0].process_samples, process_count as u64);
+
+        let range = TimeRange {
+            start_ms: 1500,
+            end_ms: None,
+        };
+        let system = query_system_samples(&conn, range, Some(session_id), None, None).unwrap();
+        let timestamps = system
+            .iter()
+            .map(|sample| sample.timestamp_ms)
+            .collect::<Vec<u64>>();
+        assert_eq!(timestamps, vec![2000, 3000]);
+        assert!(system[0].total_memory_bytes >= system[0].used_memory_bytes);
+
+        let all = TimeRange {
+            start_ms: 0,
+            end_ms: None,
+        };
+        let limited = query_system_samples(&conn, all, None, None, Some(2)).unwrap();
+        assert_eq!(limited.len(), 2);
+        // 1000 falls in the first bucket while 2000 and 3000 share the second
+        let downsampled = query_system_samples(&conn, all, None, Some(2000), None).unwrap();
+        let timestamps = downsampled
+            .iter()
+            .map(|sample| sample.timestamp_ms)
+            .collect::<Vec<u64>>();
+        assert_eq!(timestamps, vec![0, 2000]);
+
+        let own_pid = std::process::id();
+        let processes = query_process_samples(&conn, range, None, Some(own_pid), None).unwrap();
+        assert_eq!(processes.len(), 2);
+        assert_eq!(processes[0].id, own_pid.to_string());
+
+        let report = prune(&mut conn, 2500).unwrap();
+        assert_eq!(report.system_samples, 2);
+        assert_eq!(report.sessions, 0);
+        let report = prune(&mut conn, 5000).unwrap();
+        assert_eq!(report.system_samples, 1);
+        assert_eq!(report.sessions, 1);
+        assert!(list_sessions(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_default_options_cap_process_samples() {
+        let path = temp_database_path("defaults");
+        let mut conn = open_database(&path).unwrap();
+        conn.execute("INSERT INTO sessions (started_at_ms) VALUES (0)", [])
+            .unwrap();
+        let session_id = conn.last_insert_rowid();
+
+        let options = RecorderOptions::default();
+        write_batch(&mut conn, session_id, &[snapshot_at(1000)], &options).unwrap();
+
+        let range = TimeRange {
+            start_ms: 0,
+            end_ms: None,
+        };
+        let processes = query_process_samples(&conn, range, None, None, None).unwrap();
+        assert!(processes.len() <= options.max_processes_per_sample);
+        assert!(processes
+            .iter()
+            .all(|proc| proc.cpu_usage_percent >= options.min_cpu_usage_percent));
+    }
+
+    #[test]
+    fn test_recorder_state() {
+        let path = temp_database_path("state");
+        let sampler = SamplerState::start(Duration::from_millis(50));
+        let options = RecorderOptions {
+            sample_interval_ms: 0,
+            // Keep the process samples small
+            min_cpu_usage_percent: 100.0,
+            ..Default::default()
+        };
+
+        let recorder = Mutex::new(RecorderState::default());
+        let session_id = recorder
+            .lock()
+            .unwrap()
+            .start(&path, sampler.subscribe(), options)
+            .unwrap();
+        thread::sleep(Duration::from_millis(500));
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        assert_eq!(
+            rt.block_on(finish_recording(&recorder)).unwrap(),
+            Some(session_id)
+        );
+        assert_eq!(rt.block_on(finish_recording(&recorder)).unwrap(), None);
+
+        // Stopping flushes the last batch and ends the session
+        let conn = open_database(&path).unwrap();
+        let sessions = list_sessions(&conn).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, session_id);
+        assert!(sessions[0].ended_at_ms.is_some());
+        assert!(sessions[0].system_samples >= 2);
+    }
+}
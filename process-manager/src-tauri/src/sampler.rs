@@ -0,0 +1,221 @@
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use sysinfo::{
+    CpuRefreshKind, MemoryRefreshKind, Networks, ProcessRefreshKind, ProcessesToUpdate, System,
+    UpdateKind, Users,
+};
+use tokio::sync::watch;
+
+use crate::network::{get_network_interfaces, NetworkInterfaceInfo};
+use crate::process::{get_current_processes, ProcessInfo};
+use crate::system::{get_sys_info, CpuTopology, SystemInfo};
+
+// Refresh the user list every minute (at the default interval) to pick up new accounts
+const USERS_REFRESH_EVERY: u64 = 60;
+
+// A consistent view of the system taken during a single refresh
+pub(crate) struct Snapshot {
+    // Unix time in milliseconds at which the snapshot was taken
+    pub(crate) timestamp_ms: u64,
+    pub(crate) processes: Vec<ProcessInfo>,
+    pub(crate) system: SystemInfo,
+    pub(crate) networks: Vec<NetworkInterfaceInfo>,
+}
+
+/// Gets the current Unix time in milliseconds
+pub(crate) fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// Long-lived sysinfo handles that are refreshed incrementally instead of being rebuilt per call
+pub(crate) struct Sampler {
+    sys: System,
+    users: Users,
+    networks: Networks,
+    cpu_topology: CpuTopology,
+    refresh_count: u64,
+    last_refresh: Option<Instant>,
+}
+
+impl Sampler {
+    /// Creates a sampler and primes it so that the first snapshot has accurate CPU usage
+    pub(crate) fn new() -> Self {
+        let mut sampler = Sampler {
+            sys: System::new(),
+            users: Users::new_with_refreshed_list(),
+            networks: Networks::new_with_refreshed_list(),
+            cpu_topology: CpuTopology::read(),
+            refresh_count: 0,
+            last_refresh: None,
+        };
+
+        sampler.refresh();
+        thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL); // Required for accurate CPU stats
+        sampler
+    }
+
+    /// Refreshes only what the snapshots need, reusing everything sysinfo already knows
+    ///
+    /// Returns the time since the previous refresh, if any
+    fn refresh(&mut self) -> Option<Duration> {
+        self.sys
+            .refresh_cpu_specifics(CpuRefreshKind::nothing().with_cpu_usage().with_frequency());
+        self.sys
+            .refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram().with_swap());
+        self.sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_cpu()
+                .with_memory()
+                .with_disk_usage()
+                .with_user(UpdateKind::OnlyIfNotSet)
+                .with_cmd(UpdateKind::OnlyIfNotSet),
+        );
+
+        // Drop interfaces that went away (e.g., unplugged adapters or stopped VPNs)
+        self.networks.refresh(true);
+
+        self.refresh_count += 1;
+        if self.refresh_count.is_multiple_of(USERS_REFRESH_EVERY) {
+            self.users.refresh();
+        }
+
+        let now = Instant::now();
+        self.last_refresh
+            .replace(now)
+            .map(|last_refresh| now.duration_since(last_refresh))
+    }
+
+    /// Refreshes the system and takes a snapshot of it
+    ///
+    /// Returns a new `Snapshot`
+    pub(crate) fn sample(&mut self) -> Snapshot {
+        let elapsed = self.refresh();
+
+        Snapshot {
+            timestamp_ms: unix_time_ms(),
+            processes: get_current_processes(&self.sys, &self.users, elapsed),
+            system: get_sys_info(&self.sys, &self.cpu_topology),
+            networks: get_network_interfaces(&self.networks, elapsed),
+        }
+    }
+}
+
+// Managed state giving monitors and commands access to the latest snapshot
+pub struct SamplerState {
+    latest: watch::Receiver<Arc<Snapshot>>,
+    period: mpsc::Sender<Duration>,
+}
+
+impl SamplerState {
+    /// Starts sampling on a background thread
+    ///
+    /// `period` is the initial time between refreshes. The thread exits once the state is dropped
+    ///
+    /// Returns the `SamplerState` once the first snapshot is available
+    pub(crate) fn start(period: Duration) -> Self {
+        let mut sampler = Sampler::new();
+        let (sender, latest) = watch::channel(Arc::new(sampler.sample()));
+        let (period_sender, period_receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut period = period;
+            let mut last_sample = Instant::now();
+            let mut next_sample = last_sample + period;
+            loop {
+                // Wait for the next refresh, waking up early if the period changes. The deadline is
+                // absolute so that a stream of period changes can't keep pushing the refresh back
+                let wait = next_sample.saturating_duration_since(Instant::now());
+                match period_receiver.recv_timeout(wait) {
+                    Ok(new_period) => {
+                        period = new_period;
+                        next_sample = last_sample + period;
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+
+                let snapshot = sampler.sample();
+                last_sample = Instant::now();
+                next_sample = last_sample + period;
+                if sender.send(Arc::new(snapshot)).is_err() {
+                    // Every receiver is gone
+                    break;
+                }
+            }
+        });
+
+        SamplerState {
+            latest,
+            period: period_sender,
+        }
+    }
+
+    /// Gets the most recent snapshot without waiting for a refresh
+    pub(crate) fn latest(&self) -> Arc<Snapshot> {
+        self.latest.borrow().clone()
+    }
+
+    /// Gets a receiver that is notified of every new snapshot
+    pub(crate) fn subscribe(&self) -> watch::Receiver<Arc<Snapshot>> {
+        self.latest.clone()
+    }
+
+    /// Gets a sender that changes the time between refreshes
+    pub(crate) fn period_sender(&self) -> mpsc::Sender<Duration> {
+        self.period.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample() {
+        let mut sampler = Sampler::new();
+
+        let snapshot = sampler.sample();
+        assert!(!snapshot.processes.is_empty());
+        assert!(snapshot
+            .processes
+            .iter()
+            .any(|proc| proc.id == std::process::id().to_string()));
+    }
+
+    #[test]
+    fn test_sampler_state() {
+        let state = SamplerState::start(Duration::from_millis(50));
+        let first = state.latest();
+        assert!(!first.processes.is_empty());
+
+        thread::sleep(Duration::from_millis(500));
+        let second = state.latest();
+        assert!(!Arc::ptr_eq(&second, &first));
+
+        // A long period stops further refreshes
+        state.period_sender().send(Duration::from_secs(60)).unwrap();
+        thread::sleep(Duration::from_millis(200));
+        let third = state.latest();
+        thread::sleep(Duration::from_millis(200));
+        assert!(Arc::ptr_eq(&state.latest(), &third));
+
+        // A stream of period changes doesn't postpone refreshes
+        let period_sender = state.period_sender();
+        period_sender.send(Duration::from_millis(100)).unwrap();
+        let fourth = state.latest();
+        for _ in 0..25 {
+            thread::sleep(Duration::from_millis(20));
+            period_sender.send(Duration::from_millis(100)).unwrap();
+        }
+        assert!(!Arc::ptr_eq(&state.latest(), &fourth));
+    }
+}
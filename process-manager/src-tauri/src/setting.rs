@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+// Struct to contain the value of a process or thread setting before and after a change, so that
+// the change can be undone by applying `before` again
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SettingChange<T> {
+    // Process or thread ID. The main thread's ID is the process ID
+    pub(crate) id: String,
+    pub(crate) before: T,
+    pub(crate) after: T,
+}
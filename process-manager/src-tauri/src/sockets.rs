@@ -0,0 +1,429 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::Pid;
+
+use crate::process::parse_pid;
+
+// Socket table a socket was found in
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum SocketProtocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+    Unix,
+}
+
+// Transport used to look up the owner of a port. Covers both IPv4 and IPv6
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PortProtocol {
+    Tcp,
+    Udp,
+}
+
+#[cfg(target_os = "linux")]
+impl SocketProtocol {
+    const ALL: [SocketProtocol; 5] = [
+        SocketProtocol::Tcp,
+        SocketProtocol::Tcp6,
+        SocketProtocol::Udp,
+        SocketProtocol::Udp6,
+        SocketProtocol::Unix,
+    ];
+
+    /// Gets the name of the table under /proc/net
+    fn table(self) -> &'static str {
+        match self {
+            SocketProtocol::Tcp => "tcp",
+            SocketProtocol::Tcp6 => "tcp6",
+            SocketProtocol::Udp => "udp",
+            SocketProtocol::Udp6 => "udp6",
+            SocketProtocol::Unix => "unix",
+        }
+    }
+
+    fn port_protocol(self) -> Option<PortProtocol> {
+        match self {
+            SocketProtocol::Tcp | SocketProtocol::Tcp6 => Some(PortProtocol::Tcp),
+            SocketProtocol::Udp | SocketProtocol::Udp6 => Some(PortProtocol::Udp),
+            SocketProtocol::Unix => None,
+        }
+    }
+}
+
+// Struct to contain a single socket from the kernel's socket tables
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SocketInfo {
+    protocol: SocketProtocol,
+    // IP address, or the bound path for Unix sockets (empty if unbound)
+    local_address: String,
+    local_port: Option<u16>,
+    remote_address: Option<String>,
+    remote_port: Option<u16>,
+    // e.g., "LISTEN" or "ESTABLISHED"
+    state: String,
+    inode: u64,
+}
+
+// Struct to contain a process holding a socket bound to a port
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PortOwner {
+    id: String,
+    name: String,
+    socket: SocketInfo,
+}
+
+/// Decodes an address from a /proc/net table (e.g., "0100007F:1F90")
+///
+/// The kernel prints each 32-bit word of the address in host byte order, while the port is
+/// printed in network order
+///
+/// Returns the address and port or `None` if the field is malformed
+#[cfg(target_os = "linux")]
+fn parse_address(field: &str) -> Option<(String, u16)> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+
+    let mut bytes = Vec::with_capacity(16);
+    for chunk in address.as_bytes().chunks(8) {
+        let word = u32::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+    let address = match bytes.len() {
+        4 => std::net::Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?).to_string(),
+        16 => std::net::Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?).to_string(),
+        _ => return None,
+    };
+    Some((address, port))
+}
+
+/// Names the state of a TCP or UDP socket (values from include/net/tcp_states.h)
+#[cfg(target_os = "linux")]
+fn inet_state(state: u8, protocol: SocketProtocol) -> String {
+    let name = match state {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECV",
+        0x04 => "FIN_WAIT1",
+        0x05 => "FIN_WAIT2",
+        0x06 => "TIME_WAIT",
+        // Unconnected UDP sockets report TCP_CLOSE
+        0x07 if protocol.port_protocol() == Some(PortProtocol::Udp) => "UNCONNECTED",
+        0x07 => "CLOSE",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        _ => return format!("UNKNOWN({state:#04x})"),
+    };
+    name.to_owned()
+}
+
+/// Parses a line of /proc/net/{tcp,tcp6,udp,udp6}
+#[cfg(target_os = "linux")]
+fn parse_inet_line(line: &str, protocol: SocketProtocol) -> Option<SocketInfo> {
+    // sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode
+    let fields = line.split_whitespace().collect::<Vec<&str>>();
+    let (local_address, local_port) = parse_address(fields.get(1)?)?;
+    let (remote_address, remote_port) = parse_address(fields.get(2)?)?;
+    let state = u8::from_str_radix(fields.get(3)?, 16).ok()?;
+
+    Some(SocketInfo {
+        protocol,
+        local_address,
+        local_port: Some(local_port),
+        remote_address: Some(remote_address),
+        remote_port: Some(remote_port),
+        state: inet_state(state, protocol),
+        inode: fields.get(9)?.parse().ok()?,
+    })
+}
+
+/// Parses a line of /proc/net/unix
+#[cfg(target_os = "linux")]
+fn parse_unix_line(line: &str) -> Option<SocketInfo> {
+    // Num RefCount Protocol Flags Type St Inode Path
+    let fields = line.split_whitespace().collect::<Vec<&str>>();
+    let flags = u32::from_str_radix(fields.get(3)?, 16).ok()?;
+    let state = u8::from_str_radix(fields.get(5)?, 16).ok()?;
+    // __SO_ACCEPTCON marks a listening socket
+    let state = if flags & 0x10000 != 0 {
+        "LISTEN"
+    } else {
+        match state {
+            0x01 => "UNCONNECTED",
+            0x02 => "CONNECTING",
+            0x03 => "CONNECTED",
+            0x04 => "DISCONNECTING",
+            _ => "UNKNOWN",
+        }
+    };
+
+    Some(SocketInfo {
+        protocol: SocketProtocol::Unix,
+        local_address: fields.get(7).copied().unwrap_or_default().to_owned(),
+        local_port: None,
+        remote_address: None,
+        remote_port: None,
+        state: state.to_owned(),
+        inode: fields.get(6)?.parse().ok()?,
+    })
+}
+
+/// Reads a socket table
+///
+/// `net_dir` is /proc/net or /proc/<pid>/net, which shows the tables of the process' network
+/// namespace. Missing tables (e.g., tcp6 with IPv6 disabled) are treated as empty
+///
+/// Returns a vector of `SocketInfo` structs, one for each row
+#[cfg(target_os = "linux")]
+fn read_socket_table(net_dir: &str, protocol: SocketProtocol) -> Vec<SocketInfo> {
+    let Ok(table) = std::fs::read_to_string(format!("{net_dir}/{}", protocol.table())) else {
+        return Vec::new();
+    };
+
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| match protocol {
+            SocketProtocol::Unix => parse_unix_line(line),
+            _ => parse_inet_line(line, protocol),
+        })
+        .collect()
+}
+
+/// Finds the inodes of the sockets a process has open
+///
+/// `pid` is the Pid of the process
+///
+/// Returns a set of socket inodes or a String error if the process' descriptors can't be listed
+#[cfg(target_os = "linux")]
+pub(crate) fn socket_inodes(pid: Pid) -> Result<HashSet<u64>, String> {
+    let entries = std::fs::read_dir(format!("/proc/{pid}/fd"))
+        .map_err(|e| format!("Failed to list sockets of process {pid}: {e}"))?;
+
+    Ok(entries
+        .filter_map(|entry| {
+            let target = std::fs::read_link(entry.ok()?.path()).ok()?;
+            target
+                .to_str()?
+                .strip_prefix("socket:[")?
+                .strip_suffix(']')?
+                .parse()
+                .ok()
+        })
+        .collect())
+}
+
+/// Lists the sockets a process has open
+///
+/// Returns a vector of `SocketInfo` structs or a String error if the process' descriptors can't be
+/// listed
+#[cfg(target_os = "linux")]
+pub(crate) fn read_process_sockets(pid: Pid) -> Result<Vec<SocketInfo>, String> {
+    let inodes = socket_inodes(pid)?;
+    let net_dir = format!("/proc/{pid}/net");
+
+    Ok(SocketProtocol::ALL
+        .iter()
+        .flat_map(|protocol| read_socket_table(&net_dir, *protocol))
+        .filter(|socket| inodes.contains(&socket.inode))
+        .collect())
+}
+
+/// Finds the processes holding sockets bound to a local port
+///
+/// `protocol` limits the search to TCP or UDP. Processes whose descriptors can't be read are
+/// skipped, so running unprivileged may miss processes owned by other users
+///
+/// Returns a vector of `PortOwner` structs, one for each matching socket of each process
+#[cfg(target_os = "linux")]
+pub(crate) fn find_port_owners(
+    port: u16,
+    protocol: Option<PortProtocol>,
+) -> Result<Vec<PortOwner>, String> {
+    let sockets = SocketProtocol::ALL
+        .iter()
+        .filter(|socket_protocol| {
+            socket_protocol
+                .port_protocol()
+                .is_some_and(|port_protocol| protocol.is_none_or(|wanted| wanted == port_protocol))
+        })
+        .flat_map(|socket_protocol| read_socket_table("/proc/net", *socket_protocol))
+        .filter(|socket| socket.local_port == Some(port))
+        .collect::<Vec<SocketInfo>>();
+    if sockets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let entries =
+        std::fs::read_dir("/proc").map_err(|e| format!("Failed to list processes: {e}"))?;
+    let mut owners = Vec::new();
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let pid = Pid::from_u32(pid);
+        let Ok(inodes) = socket_inodes(pid) else {
+            continue;
+        };
+
+        let name = std::fs::read_to_string(format!("/proc/{pid}/comm"))
+            .map(|comm| comm.trim_end().to_owned())
+            .unwrap_or_default();
+        for socket in sockets
+            .iter()
+            .filter(|socket| inodes.contains(&socket.inode))
+        {
+            owners.push(PortOwner {
+                id: pid.to_string(),
+                name: name.clone(),
+                socket: socket.clone(),
+            });
+        }
+    }
+    Ok(owners)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn socket_inodes(_pid: Pid) -> Result<HashSet<u64>, String> {
+    Err("Not supported on this platform".to_owned())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn read_process_sockets(_pid: Pid) -> Result<Vec<SocketInfo>, String> {
+    Err("Listing sockets is only supported on Linux".to_owned())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn find_port_owners(
+    _port: u16,
+    _protocol: Option<PortProtocol>,
+) -> Result<Vec<PortOwner>, String> {
+    Err("Looking up ports is only supported on Linux".to_owned())
+}
+
+/// Lists the TCP, UDP and Unix sockets a process has open
+///
+/// `id` is the Pid of the process
+///
+/// Returns a vector of `SocketInfo` structs if successful and a String error if unsuccessful
+#[tauri::command]
+pub async fn list_process_sockets(id: &str) -> Result<Vec<SocketInfo>, String> {
+    read_process_sockets(parse_pid(id)?)
+}
+
+/// Finds the processes holding a local port
+///
+/// `port` is the local port and `proto` optionally limits the search to TCP or UDP
+///
+/// Returns a vector of `PortOwner` structs (empty if nothing holds the port) if successful and a
+/// String error if unsuccessful
+#[tauri::command]
+pub async fn find_process_by_port(
+    port: u16,
+    proto: Option<PortProtocol>,
+) -> Result<Vec<PortOwner>, String> {
+    find_port_owners(port, proto)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::net::{TcpListener, UdpSocket};
+    use std::os::unix::net::UnixListener;
+    use tokio::runtime::Runtime;
+
+    #[test]
+    fn test_parse_lines() {
+        let tcp = "   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 12345 1 0000000000000000 100 0 0 10 0";
+        let socket = parse_inet_line(tcp, SocketProtocol::Tcp).unwrap();
+        assert_eq!(socket.local_address, "127.0.0.1");
+        assert_eq!(socket.local_port, Some(8080));
+        assert_eq!(socket.remote_address.as_deref(), Some("0.0.0.0"));
+        assert_eq!(socket.state, "LISTEN");
+        assert_eq!(socket.inode, 12345);
+
+        let tcp6 = "   0: 00000000000000000000000001000000:0016 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 6789 1 0000000000000000 100 0 0 10 0";
+        let socket = parse_inet_line(tcp6, SocketProtocol::Tcp6).unwrap();
+        assert_eq!(socket.local_address, "::1");
+        assert_eq!(socket.local_port, Some(22));
+
+        let udp = "  5: 00000000:14E9 00000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 4242 2 0000000000000000 0";
+        let socket = parse_inet_line(udp, SocketProtocol::Udp).unwrap();
+        assert_eq!(socket.state, "UNCONNECTED");
+        assert_eq!(socket.local_port, Some(5353));
+
+        let unix = "0000000000000000: 00000002 00000000 00010000 0001 01 5555 /run/test.sock";
+        let socket = parse_unix_line(unix).unwrap();
+        assert_eq!(socket.local_address, "/run/test.sock");
+        assert_eq!(socket.state, "LISTEN");
+        assert_eq!(socket.inode, 5555);
+
+        assert!(parse_inet_line("garbage", SocketProtocol::Tcp).is_none());
+    }
+
+    #[test]
+    fn test_list_process_sockets() {
+        let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+        let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let unix_path = std::env::temp_dir().join(format!("sockets-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&unix_path);
+        let unix = UnixListener::bind(&unix_path).unwrap();
+
+        let rt = Runtime::new().unwrap();
+        let sockets = rt
+            .block_on(list_process_sockets(&std::process::id().to_string()))
+            .unwrap();
+        let tcp_port = tcp.local_addr().unwrap().port();
+        assert!(sockets
+            .iter()
+            .any(|socket| socket.protocol == SocketProtocol::Tcp
+                && socket.local_port == Some(tcp_port)
+                && socket.state == "LISTEN"));
+        let udp_port = udp.local_addr().unwrap().port();
+        assert!(sockets
+            .iter()
+            .any(|socket| socket.protocol == SocketProtocol::Udp
+                && socket.local_port == Some(udp_port)));
+        assert!(sockets
+            .iter()
+            .any(|socket| socket.protocol == SocketProtocol::Unix
+                && socket.local_address == unix_path.to_string_lossy()));
+
+        drop(unix);
+        std::fs::remove_file(&unix_path).unwrap();
+    }
+
+    #[test]
+    fn test_find_process_by_port() {
+        let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = tcp.local_addr().unwrap().port();
+        let own_id = std::process::id().to_string();
+
+        let rt = Runtime::new().unwrap();
+        let owners = rt
+            .block_on(find_process_by_port(port, Some(PortProtocol::Tcp)))
+            .unwrap();
+        assert!(owners.iter().any(|owner| owner.id == own_id));
+        assert!(
+            !owners.is_empty()
+                && owners
+                    .iter()
+                    .all(|owner| owner.socket.local_port == Some(port))
+        );
+
+        // Nothing holds the TCP port over UDP
+        let owners = rt
+            .block_on(find_process_by_port(port, Some(PortProtocol::Udp)))
+            .unwrap();
+        assert!(owners.iter().all(|owner| owner.id != own_id));
+    }
+}
@@ -1,11 +1,11 @@
-use crate::MonitorUpdateState;
+use crate::monitor::{
+    emit_payload, event_payload_version, monitor_period, MonitorKind, MonitorRegistry,
+};
+use crate::sampler::SamplerState;
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
-use std::thread::sleep;
-use std::time::Duration;
-use sysinfo::{DiskRefreshKind, Disks, MemoryRefreshKind, System};
-use tauri::{AppHandle, Emitter, Manager, State};
-use tokio::time::interval;
+use sysinfo::{CpuRefreshKind, DiskRefreshKind, Disks, RefreshKind, System};
+use tauri::{AppHandle, Manager, State};
 
 // Struct to contain system info
 #[derive(Serialize, Deserialize, Clone)]
@@ -13,18 +13,160 @@ pub struct SystemInfo {
     name: String,
     os: String,
     cpu_arch: String,
-    cpu_usage_percent: f32,
+    pub(crate) cpu_usage_percent: f32,
+    // Presentation strings for the matching `_bytes` fields
     total_memory: String,
     used_memory: String,
+    available_memory: String,
+    total_swap: String,
+    used_swap: String,
+    pub(crate) total_memory_bytes: u64,
+    // Total minus available, so reclaimable page cache doesn't count as used
+    pub(crate) used_memory_bytes: u64,
+    available_memory_bytes: u64,
+    total_swap_bytes: u64,
+    used_swap_bytes: u64,
+    // Only available on Linux
+    memory_breakdown: Option<MemoryBreakdown>,
+    cpu_brand: String,
+    cpu_vendor: String,
+    // None if the platform doesn't report it
+    physical_core_count: Option<usize>,
+    logical_core_count: usize,
+    // One entry per logical core, in the order listed by `System::cpus()`
+    cores: Vec<CoreInfo>,
+    load_average: LoadAverage,
     // TODO: GPU?
 }
 
+// Struct to contain the usage of a single logical core
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CoreInfo {
+    // Kernel name of the core (e.g., "cpu3")
+    name: String,
+    usage_percent: f32,
+    frequency_mhz: u64,
+    // None if the platform doesn't expose the maximum frequency
+    max_frequency_mhz: Option<u64>,
+}
+
+// Where RAM goes, as reported by /proc/meminfo
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct MemoryBreakdown {
+    // Page cache, excluding swap cache
+    cached_bytes: u64,
+    // Block device metadata cache
+    buffers_bytes: u64,
+    // tmpfs and shared anonymous memory. Counted in `cached_bytes` but can't be dropped
+    shared_bytes: u64,
+    // Kernel object caches, `slab_reclaimable_bytes` of which can be freed under pressure
+    slab_bytes: u64,
+    slab_reclaimable_bytes: u64,
+    // Modified pages waiting to be, or being, written back to disk
+    dirty_bytes: u64,
+    writeback_bytes: u64,
+    // Transparent huge pages backing anonymous memory
+    anon_huge_pages_bytes: u64,
+    // Preallocated huge page pool, counted in pages of `huge_page_size_bytes`
+    huge_pages_total: u64,
+    huge_pages_free: u64,
+    huge_page_size_bytes: u64,
+    // Memory promised to processes (Committed_AS) and the most that can be promised under strict
+    // overcommit (CommitLimit)
+    committed_bytes: u64,
+    commit_limit_bytes: u64,
+}
+
+// Average number of runnable processes over the last 1, 5 and 15 minutes. Always 0 on Windows
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LoadAverage {
+    one: f64,
+    five: f64,
+    fifteen: f64,
+}
+
+// CPU details that don't change while the app runs, read once instead of on every sample
+pub(crate) struct CpuTopology {
+    brand: String,
+    vendor: String,
+    physical_core_count: Option<usize>,
+    // Indexed like `System::cpus()`
+    max_frequencies_mhz: Vec<Option<u64>>,
+}
+
+impl CpuTopology {
+    /// Reads the CPU model and core layout
+    ///
+    /// Returns a new `CpuTopology`
+    pub(crate) fn read() -> Self {
+        let sys =
+            System::new_with_specifics(RefreshKind::nothing().with_cpu(CpuRefreshKind::nothing()));
+        let first_cpu = sys.cpus().first();
+
+        CpuTopology {
+            brand: first_cpu
+                .map(|cpu| cpu.brand().trim().to_owned())
+                .unwrap_or_default(),
+            vendor: first_cpu
+                .map(|cpu| cpu.vendor_id().to_owned())
+                .unwrap_or_default(),
+            physical_core_count: System::physical_core_count(),
+            max_frequencies_mhz: sys
+                .cpus()
+                .iter()
+                .map(|cpu| max_frequency_mhz(cpu.name()))
+                .collect(),
+        }
+    }
+}
+
+/// Reads the maximum frequency of a core from cpufreq
+///
+/// `name` is the kernel name of the core (e.g., "cpu3")
+///
+/// Returns the frequency in MHz or `None` if cpufreq isn't available
+#[cfg(target_os = "linux")]
+fn max_frequency_mhz(name: &str) -> Option<u64> {
+    let khz = std::fs::read_to_string(format!(
+        "/sys/devices/system/cpu/{name}/cpufreq/cpuinfo_max_freq"
+    ))
+    .ok()?;
+    Some(khz.trim().parse::<u64>().ok()? / 1000)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn max_frequency_mhz(_name: &str) -> Option<u64> {
+    None
+}
+
+// Type of storage device backing a disk
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum DiskKind {
+    Ssd,
+    Hdd,
+    Unknown,
+}
+
 // Struct to contain disk info
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DiskInfo {
     name: String,
+    mount_point: String,
+    // e.g., "ext4", "btrfs", "vfat"
+    file_system: String,
+    kind: DiskKind,
+    removable: bool,
+    read_only: bool,
+    // Presentation strings for `total_space_bytes` and `used_space_bytes`
     total_space: String,
     used_space: String,
+    total_space_bytes: u64,
+    used_space_bytes: u64,
+    // None if the file system has no fixed inode table (e.g., btrfs, vfat) or the platform isn't
+    // supported
+    total_inodes: Option<u64>,
+    used_inodes: Option<u64>,
 }
 
 /// Formats given number of bytes into a readable String
@@ -50,15 +192,84 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
+/// Parses the contents of /proc/meminfo
+///
+/// Values in kB are converted to bytes and missing fields are left at 0
+///
+/// Returns a `MemoryBreakdown`
+#[cfg(target_os = "linux")]
+fn parse_meminfo(meminfo: &str) -> MemoryBreakdown {
+    let mut breakdown = MemoryBreakdown::default();
+
+    for line in meminfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        // Page counts have no unit
+        let value = match value.strip_suffix("kB") {
+            Some(kb) => kb.trim().parse::<u64>().map(|kb| kb * 1024),
+            None => value.parse::<u64>(),
+        };
+        let Ok(value) = value else {
+            continue;
+        };
+
+        let field = match key {
+            "Cached" => &mut breakdown.cached_bytes,
+            "Buffers" => &mut breakdown.buffers_bytes,
+            "Shmem" => &mut breakdown.shared_bytes,
+            "Slab" => &mut breakdown.slab_bytes,
+            "SReclaimable" => &mut breakdown.slab_reclaimable_bytes,
+            "Dirty" => &mut breakdown.dirty_bytes,
+            "Writeback" => &mut breakdown.writeback_bytes,
+            "AnonHugePages" => &mut breakdown.anon_huge_pages_bytes,
+            "HugePages_Total" => &mut breakdown.huge_pages_total,
+            "HugePages_Free" => &mut breakdown.huge_pages_free,
+            "Hugepagesize" => &mut breakdown.huge_page_size_bytes,
+            "Committed_AS" => &mut breakdown.committed_bytes,
+            "CommitLimit" => &mut breakdown.commit_limit_bytes,
+            _ => continue,
+        };
+        *field = value;
+    }
+    breakdown
+}
+
+/// Reads the memory breakdown of the system
+///
+/// Returns a `MemoryBreakdown` or `None` if /proc/meminfo can't be read
+#[cfg(target_os = "linux")]
+fn read_memory_breakdown() -> Option<MemoryBreakdown> {
+    std::fs::read_to_string("/proc/meminfo")
+        .ok()
+        .map(|meminfo| parse_meminfo(&meminfo))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_memory_breakdown() -> Option<MemoryBreakdown> {
+    None
+}
+
 /// Gets system information such as Hostname, OS, CPU stats, RAM stats, etc.
 ///
+/// `sys` must already have refreshed CPU usage, CPU frequency, RAM and swap. `topology` supplies
+/// the CPU details that aren't refreshed
+///
 /// Returns a `SystemInfo` struct containing system information
-fn get_sys_info() -> SystemInfo {
-    let mut sys = System::new_all();
-    sys.refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram());
-
-    sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL); // Required for accurate CPU usage stats
-    sys.refresh_cpu_usage();
+pub(crate) fn get_sys_info(sys: &System, topology: &CpuTopology) -> SystemInfo {
+    let cores = sys
+        .cpus()
+        .iter()
+        .enumerate()
+        .map(|(index, cpu)| CoreInfo {
+            name: cpu.name().to_owned(),
+            usage_percent: cpu.cpu_usage(),
+            frequency_mhz: cpu.frequency(),
+            max_frequency_mhz: topology.max_frequencies_mhz.get(index).copied().flatten(),
+        })
+        .collect::<Vec<CoreInfo>>();
+    let load_average = System::load_average();
 
     SystemInfo {
         name: System::host_name().unwrap_or("<Unknown>".to_owned()),
@@ -67,73 +278,167 @@ fn get_sys_info() -> SystemInfo {
         cpu_usage_percent: sys.global_cpu_usage(),
         total_memory: format_bytes(sys.total_memory()),
         used_memory: format_bytes(sys.used_memory()),
+        available_memory: format_bytes(sys.available_memory()),
+        total_swap: format_bytes(sys.total_swap()),
+        used_swap: format_bytes(sys.used_swap()),
+        total_memory_bytes: sys.total_memory(),
+        used_memory_bytes: sys.used_memory(),
+        available_memory_bytes: sys.available_memory(),
+        total_swap_bytes: sys.total_swap(),
+        used_swap_bytes: sys.used_swap(),
+        memory_breakdown: read_memory_breakdown(),
+        cpu_brand: topology.brand.clone(),
+        cpu_vendor: topology.vendor.clone(),
+        physical_core_count: topology.physical_core_count,
+        logical_core_count: cores.len(),
+        cores,
+        load_average: LoadAverage {
+            one: load_average.one,
+            five: load_average.five,
+            fifteen: load_average.fifteen,
+        },
     }
 }
 
 /// Regularly updates frontend on system resource usage
 ///
 /// `app` is used to emit event to the frontend
+///
+/// `interval_ms` is the polling interval (one second by default) and `adaptive` slows polling down
+/// while the window is hidden or minimized
+///
+/// `payload_version` selects the event shape. Version 1 (the default) emits the bare `SystemInfo`
+/// and version 2 wraps it in a `VersionedPayload`
+///
+/// Starting a new system monitor replaces the running one
+///
+/// Returns the ID of the new monitor or a String error if the interval or payload version is
+/// invalid
 #[tauri::command]
-pub async fn monitor_sys_info(app: AppHandle) {
-    // Poll for system update every second
-    let mut interval_timer = interval(Duration::from_millis(1000));
-
-    // NOTE: Use tauri::async_runtime::spawn() instead?
-    tokio::spawn(async move {
-        loop {
-            // Check state and exit loop if the flag is set
-            let stop_updates = {
-                let state = app.state::<Mutex<MonitorUpdateState>>();
-                let state_guard = state.lock().unwrap();
-
-                state_guard.stop_system_updates
-            };
-            if stop_updates {
-                println!("Stopping system updates");
-                break;
-            }
-            interval_timer.tick().await;
+pub async fn monitor_sys_info(
+    app: AppHandle,
+    interval_ms: Option<u64>,
+    adaptive: Option<bool>,
+    payload_version: Option<u32>,
+) -> Result<u64, String> {
+    let period = monitor_period(interval_ms)?;
+    let version = event_payload_version(payload_version)?;
+    let tick_app = app.clone();
+    let tick = move || {
+        let snapshot = tick_app.state::<SamplerState>().latest();
+        let sys_info = snapshot.system.clone();
+        // Emit the event globally and handle potential error
+        if let Err(err) = emit_payload(
+            &tick_app,
+            "system_update",
+            version,
+            snapshot.timestamp_ms,
+            sys_info,
+        ) {
+            eprintln!("Failed to emit system_update event. Error: {err}");
+        };
+    };
 
-            let sys_info = get_sys_info();
-            // Emit the event globally and handle potential error
-            if let Err(err) = app.emit("system_update", sys_info) {
-                eprintln!("Failed to emit system_update event. Error: {err}");
-            };
-        }
-    });
+    let registry = app.state::<Mutex<MonitorRegistry>>();
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    Ok(registry_guard.start(MonitorKind::System, period, adaptive.unwrap_or(false), tick))
 }
 
+/// Stops the running system monitor, if any
+///
+/// `registry` is a reference to the MonitorRegistry injected by Tauri
+///
+/// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
 #[tauri::command]
-pub fn stop_monitoring_system(state: State<'_, Mutex<MonitorUpdateState>>) -> Result<(), String> {
-    if let Ok(mut state_guard) = state.lock() {
-        state_guard.stop_system_updates = true;
+pub fn stop_monitoring_system(registry: State<'_, Mutex<MonitorRegistry>>) -> Result<(), String> {
+    if let Ok(mut registry_guard) = registry.lock() {
+        registry_guard.stop_kind(MonitorKind::System);
     } else {
-        return Err("Failed to acquire lock on monitoring state".to_owned());
+        return Err("Failed to acquire lock on monitor registry".to_owned());
     };
     Ok(())
 }
 
-/// Gets all disks on the system
+/// Gets what `get_disk_info` needs to be refreshed on disks
+pub(crate) fn disk_refresh_kind() -> DiskRefreshKind {
+    DiskRefreshKind::nothing().with_storage().with_kind()
+}
+
+/// Reads the inode counts of a mounted file system
 ///
-/// Returns a vector of `DiskInfo` structs, one for each disk
-#[tauri::command]
-pub fn get_all_disks() -> Vec<DiskInfo> {
-    let sys_disks =
-        Disks::new_with_refreshed_list_specifics(DiskRefreshKind::nothing().with_storage());
+/// Returns the total and used inodes or `None` if the file system doesn't report them
+#[cfg(target_os = "linux")]
+fn read_inodes(mount_point: &std::path::Path) -> Option<(u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(mount_point.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statvfs only writes to the struct and `path` is a valid NUL-terminated string
+    let stat = unsafe {
+        let mut stat = std::mem::zeroed::<libc::statvfs>();
+        if libc::statvfs(path.as_ptr(), &mut stat) != 0 {
+            return None;
+        }
+        stat
+    };
 
-    sys_disks
+    let total = stat.f_files as u64;
+    (total > 0).then(|| (total, total.saturating_sub(stat.f_ffree as u64)))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_inodes(_mount_point: &std::path::Path) -> Option<(u64, u64)> {
+    None
+}
+
+/// Builds the list of mounted disks
+///
+/// `disks` must already have refreshed storage and kind (see `disk_refresh_kind`)
+///
+/// Returns a vector of `DiskInfo` structs, one for each disk
+pub(crate) fn get_disk_info(disks: &Disks) -> Vec<DiskInfo> {
+    disks
         .iter()
-        .map(|disk| DiskInfo {
-            name: disk.name().to_string_lossy().into_owned(),
-            total_space: format_bytes(disk.total_space()),
-            used_space: format_bytes(disk.total_space() - disk.available_space()),
+        .map(|disk| {
+            let used_space_bytes = disk.total_space().saturating_sub(disk.available_space());
+            let inodes = read_inodes(disk.mount_point());
+            DiskInfo {
+                name: disk.name().to_string_lossy().into_owned(),
+                mount_point: disk.mount_point().to_string_lossy().into_owned(),
+                file_system: disk.file_system().to_string_lossy().into_owned(),
+                kind: match disk.kind() {
+                    sysinfo::DiskKind::SSD => DiskKind::Ssd,
+                    sysinfo::DiskKind::HDD => DiskKind::Hdd,
+                    sysinfo::DiskKind::Unknown(_) => DiskKind::Unknown,
+                },
+                removable: disk.is_removable(),
+                read_only: disk.is_read_only(),
+                total_space: format_bytes(disk.total_space()),
+                used_space: format_bytes(used_space_bytes),
+                total_space_bytes: disk.total_space(),
+                used_space_bytes,
+                total_inodes: inodes.map(|(total, _)| total),
+                used_inodes: inodes.map(|(_, used)| used),
+            }
         })
         .collect::<Vec<DiskInfo>>()
 }
 
+/// Gets all disks on the system
+///
+/// Returns a vector of `DiskInfo` structs, one for each disk
+#[tauri::command]
+pub fn get_all_disks() -> Vec<DiskInfo> {
+    get_disk_info(&Disks::new_with_refreshed_list_specifics(
+        disk_refresh_kind(),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sampler::Sampler;
     use std::env;
 
     #[test]
@@ -149,7 +454,7 @@ mod tests {
 
     #[test]
     fn test_get_system_info() {
-        let info = get_sys_info();
+        let info = Sampler::new().sample().system;
 
         assert!(!info.name.is_empty());
         assert_eq!(
@@ -170,6 +475,61 @@ mod tests {
 
         assert!(!info.used_memory.is_empty());
         assert!(info.used_memory.contains("B"));
+
+        assert!(info.total_memory_bytes > 0);
+        assert!(info.used_memory_bytes <= info.total_memory_bytes);
+        assert!(info.available_memory_bytes <= info.total_memory_bytes);
+        assert!(info.used_swap_bytes <= info.total_swap_bytes);
+        assert!(info.total_swap.contains("B"));
+        if cfg!(target_os = "linux") {
+            let breakdown = info.memory_breakdown.as_ref().unwrap();
+            assert!(breakdown.commit_limit_bytes > 0);
+            assert!(breakdown.slab_reclaimable_bytes <= breakdown.slab_bytes);
+        }
+
+        assert!(info.logical_core_count > 0);
+        assert_eq!(info.cores.len(), info.logical_core_count);
+        if let Some(physical_core_count) = info.physical_core_count {
+            assert!(physical_core_count <= info.logical_core_count);
+        }
+        for core in &info.cores {
+            assert!(!core.name.is_empty());
+            assert!((0.0..=100.0).contains(&core.usage_percent));
+        }
+        assert!(info.load_average.one >= 0.0);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_parse_meminfo() {
+        let meminfo = "MemTotal:        6158152 kB\n\
+                       Buffers:          103264 kB\n\
+                       Cached:          4066844 kB\n\
+                       Dirty:               448 kB\n\
+                       Shmem:              9288 kB\n\
+                       Slab:             202424 kB\n\
+                       SReclaimable:     171936 kB\n\
+                       CommitLimit:     3079076 kB\n\
+                       Committed_AS:     337260 kB\n\
+                       HugePages_Total:       4\n\
+                       HugePages_Free:        1\n\
+                       Hugepagesize:       2048 kB\n\
+                       Malformed line\n";
+        let breakdown = parse_meminfo(meminfo);
+
+        assert_eq!(breakdown.buffers_bytes, 103264 * 1024);
+        assert_eq!(breakdown.cached_bytes, 4066844 * 1024);
+        assert_eq!(breakdown.dirty_bytes, 448 * 1024);
+        assert_eq!(breakdown.shared_bytes, 9288 * 1024);
+        assert_eq!(breakdown.slab_bytes, 202424 * 1024);
+        assert_eq!(breakdown.slab_reclaimable_bytes, 171936 * 1024);
+        assert_eq!(breakdown.commit_limit_bytes, 3079076 * 1024);
+        assert_eq!(breakdown.committed_bytes, 337260 * 1024);
+        assert_eq!(breakdown.huge_pages_total, 4);
+        assert_eq!(breakdown.huge_pages_free, 1);
+        assert_eq!(breakdown.huge_page_size_bytes, 2 * 1024 * 1024);
+        // Missing from the input
+        assert_eq!(breakdown.writeback_bytes, 0);
     }
 
     #[test]
@@ -184,6 +544,15 @@ mod tests {
 
             assert!(!disk.used_space.is_empty());
             assert!(disk.used_space.contains("B"));
+
+            assert!(disk.used_space_bytes <= disk.total_space_bytes);
+
+            assert!(!disk.mount_point.is_empty());
+            assert!(!disk.file_system.is_empty());
+            assert_eq!(disk.total_inodes.is_some(), disk.used_inodes.is_some());
+            if let (Some(total), Some(used)) = (disk.total_inodes, disk.used_inodes) {
+                assert!(used <= total);
+            }
         }
     }
 }
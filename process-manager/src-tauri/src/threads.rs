@@ -0,0 +1,340 @@
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::Pid;
+use tauri::{AppHandle, Manager, State};
+
+use crate::monitor::{
+    emit_payload, event_payload_version, monitor_period, MonitorKind, MonitorRegistry,
+};
+use crate::process::parse_pid;
+use crate::sampler::unix_time_ms;
+
+// Struct to contain a single thread of a process
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ThreadInfo {
+    // Thread ID (TID)
+    id: String,
+    // Thread name (comm), truncated by the kernel to 15 bytes
+    name: String,
+    status: String,
+    // Share of total CPU capacity, like `ProcessInfo::cpu_usage_percent`. 0 on the first sample
+    cpu_usage_percent: f32,
+    // Total user and system CPU time
+    cpu_time_ms: u64,
+    // CPU the thread last ran on
+    processor: u32,
+    nice: i32,
+}
+
+// Payload of `thread_update` events
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ThreadList {
+    id: String,
+    // Sorted by CPU usage, busiest first. Empty once the process has exited
+    threads: Vec<ThreadInfo>,
+}
+
+// Fields read from /proc/<pid>/task/<tid>/stat
+#[cfg(target_os = "linux")]
+struct ThreadStat {
+    name: String,
+    status: String,
+    cpu_ticks: u64,
+    processor: u32,
+    nice: i32,
+}
+
+/// Names a scheduler state the way sysinfo's `ProcessStatus` displays it
+#[cfg(target_os = "linux")]
+fn thread_status(state: &str) -> &'static str {
+    match state {
+        "R" => "Runnable",
+        "S" => "Sleeping",
+        "D" => "UninterruptibleDiskSleep",
+        "Z" => "Zombie",
+        "T" => "Stopped",
+        "t" => "Tracing",
+        "X" | "x" => "Dead",
+        "K" => "Wakekill",
+        "W" => "Waking",
+        "P" => "Parked",
+        "I" => "Idle",
+        _ => "Unknown",
+    }
+}
+
+/// Parses the contents of a thread's stat file
+///
+/// Returns the `ThreadStat` or `None` if the contents are malformed
+#[cfg(target_os = "linux")]
+fn parse_thread_stat(stat: &str) -> Option<ThreadStat> {
+    // The name is wrapped in parentheses and may itself contain spaces or parentheses
+    let (head, rest) = stat.rsplit_once(')')?;
+    let (_, name) = head.split_once('(')?;
+    let fields = rest.split_whitespace().collect::<Vec<&str>>();
+    // Field N of the man page is at index N - 3 once the pid and name are removed
+    let field = |number: usize| fields.get(number - 3).copied();
+
+    Some(ThreadStat {
+        name: name.to_owned(),
+        status: thread_status(field(3)?).to_owned(),
+        cpu_ticks: field(14)?.parse::<u64>().ok()? + field(15)?.parse::<u64>().ok()?,
+        nice: field(19)?.parse().ok()?,
+        processor: field(39)?.parse().ok()?,
+    })
+}
+
+// Reads the threads of one process, keeping the previous sample to compute CPU usage
+#[cfg(target_os = "linux")]
+pub(crate) struct ThreadSampler {
+    pid: Pid,
+    clock_ticks_per_sec: f64,
+    cpu_count: f64,
+    // CPU ticks of each thread at the previous sample
+    previous: HashMap<u32, u64>,
+    previous_at: Option<Instant>,
+}
+
+#[cfg(target_os = "linux")]
+impl ThreadSampler {
+    pub(crate) fn new(pid: Pid) -> Self {
+        // SAFETY: sysconf has no preconditions
+        let (clock_ticks, cpu_count) = unsafe {
+            (
+                libc::sysconf(libc::_SC_CLK_TCK),
+                libc::sysconf(libc::_SC_NPROCESSORS_ONLN),
+            )
+        };
+
+        ThreadSampler {
+            pid,
+            clock_ticks_per_sec: clock_ticks.max(1) as f64,
+            cpu_count: cpu_count.max(1) as f64,
+            previous: HashMap::new(),
+            previous_at: None,
+        }
+    }
+
+    /// Reads every thread of the process
+    ///
+    /// Threads that exit while being read are skipped. CPU usage is averaged over the time since
+    /// the previous sample
+    ///
+    /// Returns a vector of `ThreadInfo` structs, busiest first, or a String error if the process'
+    /// threads can't be listed
+    pub(crate) fn sample(&mut self) -> Result<Vec<ThreadInfo>, String> {
+        let pid = self.pid;
+        let entries = std::fs::read_dir(format!("/proc/{pid}/task"))
+            .map_err(|e| format!("Failed to list threads of process {pid}: {e}"))?;
+        let now = Instant::now();
+        let elapsed_secs = self
+            .previous_at
+            .map(|previous_at| now.duration_since(previous_at).as_secs_f64())
+            .filter(|elapsed_secs| *elapsed_secs > 0.0);
+
+        let mut current = HashMap::new();
+        let mut threads = entries
+            .filter_map(|entry| {
+                let tid = entry.ok()?.file_name().to_str()?.parse::<u32>().ok()?;
+                let stat = std::fs::read_to_string(format!("/proc/{pid}/task/{tid}/stat")).ok()?;
+                let stat = parse_thread_stat(&stat)?;
+                current.insert(tid, stat.cpu_ticks);
+
+                let cpu_usage_percent = match (elapsed_secs, self.previous.get(&tid)) {
+                    (Some(elapsed_secs), Some(previous_ticks)) => {
+                        let ticks = stat.cpu_ticks.saturating_sub(*previous_ticks) as f64;
+                        (ticks / self.clock_ticks_per_sec / elapsed_secs / self.cpu_count * 100.0)
+                            as f32
+                    }
+                    _ => 0.0,
+                };
+                Some(ThreadInfo {
+                    id: tid.to_string(),
+                    name: stat.name,
+                    status: stat.status,
+                    cpu_usage_percent,
+                    cpu_time_ms: (stat.cpu_ticks as f64 * 1000.0 / self.clock_ticks_per_sec) as u64,
+                    processor: stat.processor,
+                    nice: stat.nice,
+                })
+            })
+            .collect::<Vec<ThreadInfo>>();
+        self.previous = current;
+        self.previous_at = Some(now);
+
+        threads.sort_by(|a, b| {
+            b.cpu_usage_percent
+                .partial_cmp(&a.cpu_usage_percent)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Ok(threads)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) struct ThreadSampler;
+
+#[cfg(not(target_os = "linux"))]
+impl ThreadSampler {
+    pub(crate) fn new(_pid: Pid) -> Self {
+        ThreadSampler
+    }
+
+    pub(crate) fn sample(&mut self) -> Result<Vec<ThreadInfo>, String> {
+        Err("Listing threads is only supported on Linux".to_owned())
+    }
+}
+
+/// Lists the threads of a process with their state, CPU usage, last CPU and nice value
+///
+/// `id` is the Pid of the process. CPU usage is measured over a short interval, so the command
+/// takes about 200 ms
+///
+/// Returns a vector of `ThreadInfo` structs, busiest first, if successful and a String error if
+/// unsuccessful
+#[tauri::command]
+pub async fn list_threads(id: &str) -> Result<Vec<ThreadInfo>, String> {
+    let mut sampler = ThreadSampler::new(parse_pid(id)?);
+    sampler.sample()?;
+    tokio::time::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL).await;
+    sampler.sample()
+}
+
+/// Regularly updates the frontend on the threads of one process
+///
+/// `app` is used to emit `thread_update` events carrying a `ThreadList`. Once the process exits,
+/// the list is empty
+///
+/// `id` is the Pid of the process. `interval_ms`, `adaptive` and `payload_version` work like they
+/// do for `monitor_processes`
+///
+/// Starting a new thread monitor replaces the running one, so only one process is watched at a
+/// time
+///
+/// Returns the ID of the new monitor or a String error if the ID, interval or payload version is
+/// invalid
+#[tauri::command]
+pub async fn monitor_threads(
+    app: AppHandle,
+    id: &str,
+    interval_ms: Option<u64>,
+    adaptive: Option<bool>,
+    payload_version: Option<u32>,
+) -> Result<u64, String> {
+    let pid = parse_pid(id)?;
+    let period = monitor_period(interval_ms)?;
+    let version = event_payload_version(payload_version)?;
+
+    let tick_app = app.clone();
+    let mut sampler = ThreadSampler::new(pid);
+    let tick = move || {
+        let threads = sampler.sample().unwrap_or_default();
+        let thread_list = ThreadList {
+            id: pid.to_string(),
+            threads,
+        };
+
+        // Emit the event globally and handle potential error
+        if let Err(err) = emit_payload(
+            &tick_app,
+            "thread_update",
+            version,
+            unix_time_ms(),
+            thread_list,
+        ) {
+            eprintln!("Failed to emit thread_update event. Error: {err}");
+        };
+    };
+
+    let registry = app.state::<Mutex<MonitorRegistry>>();
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    Ok(registry_guard.start(
+        MonitorKind::Threads,
+        period,
+        adaptive.unwrap_or(false),
+        tick,
+    ))
+}
+
+/// Stops the running thread monitor, if any
+///
+/// `registry` is a reference to the MonitorRegistry injected by Tauri
+///
+/// Returns a Unit Type (null in JavaScript) if successful and a String error if unsuccessful
+#[tauri::command]
+pub fn stop_monitoring_threads(registry: State<'_, Mutex<MonitorRegistry>>) -> Result<(), String> {
+    let Ok(mut registry_guard) = registry.lock() else {
+        return Err("Failed to acquire lock on monitor registry".to_owned());
+    };
+    registry_guard.stop_kind(MonitorKind::Threads);
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_parse_thread_stat() {
+        let stat = "4242 (worker (1)) R 1 4242 4242 0 -1 4194304 100 0 0 0 250 50 0 0 20 5 3 0 \
+                    12345 1000000 200 18446744073709551615 1 1 0 0 0 0 0 0 0 0 0 0 17 6 0 0 0 0 0";
+        let stat = parse_thread_stat(stat).unwrap();
+        assert_eq!(stat.name, "worker (1)");
+        assert_eq!(stat.status, "Runnable");
+        assert_eq!(stat.cpu_ticks, 300);
+        assert_eq!(stat.nice, 5);
+        assert_eq!(stat.processor, 6);
+
+        assert!(parse_thread_stat("4242 (truncated) S 1").is_none());
+    }
+
+    #[test]
+    fn test_list_threads() {
+        // Keep one named thread busy while the threads are sampled
+        let running = Arc::new(AtomicBool::new(true));
+        let busy_running = running.clone();
+        let busy = thread::Builder::new()
+            .name("busy-worker".to_owned())
+            .spawn(move || {
+                while busy_running.load(Ordering::Relaxed) {
+                    std::hint::spin_loop();
+                }
+            })
+            .unwrap();
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let threads = rt
+            .block_on(list_threads(&std::process::id().to_string()))
+            .unwrap();
+        running.store(false, Ordering::Relaxed);
+        busy.join().unwrap();
+
+        let busy = threads
+            .iter()
+            .find(|thread| thread.name == "busy-worker")
+            .unwrap();
+        assert!(busy.cpu_usage_percent > 0.0);
+        assert!(busy.cpu_time_ms > 0);
+        assert!(threads
+            .iter()
+            .any(|thread| thread.id == std::process::id().to_string()));
+        assert!(threads
+            .windows(2)
+            .all(|pair| pair[0].cpu_usage_percent >= pair[1].cpu_usage_percent));
+
+        assert!(rt
+            .block_on(list_threads("999999999"))
+            .unwrap_err()
+            .contains("Failed to list threads of process 999999999"));
+    }
+}
@@ -17,33 +17,6 @@ interface ProgressBarProps {
     backgroundColor: string,
 }
 
-// Helper function to parse memory strings into bytes
-function memoryStringToBytes(memString: string): number {
-    const parts = memString.split(" ");
-
-    // Get value and unit from parts
-    const value = parseFloat(parts[0]);
-    const unit = parts[1];
-
-    // Return memory in bytes based on unit for accurate percentages
-    switch (unit) {
-        case "B":
-            return value;
-        case "KB":
-            return value * Math.pow(1024, 1);
-        case "MB":
-            return value * Math.pow(1024, 2);
-        case "GB":
-            return value * Math.pow(1024, 3);
-        case "TB":
-            return value * Math.pow(1024, 4);
-        case "PB":
-            return value * Math.pow(1024, 5);
-        default:
-            return 0;
-    }
-}
-
 // Progress ring component for displaying system CPU and RAM usage
 function ProgressRing({radius, stroke, progress, color, backgroundColor, label}: ProgressRingProps) {
     const normalizedRadius = radius - stroke * 2;
@@ -114,8 +87,8 @@ function ProgressBar({total, completed, progress, color, backgroundColor}: Progr
 
 export default function System({sysInfo, disks}: { sysInfo: SystemInfo, disks: DiskInfo[] }) {
     // Calculate memory usage percentage
-    const totalMemoryInBytes = memoryStringToBytes(sysInfo.total_memory);
-    const usedMemoryInBytes = memoryStringToBytes(sysInfo.used_memory);
+    const totalMemoryInBytes = sysInfo.total_memory_bytes;
+    const usedMemoryInBytes = sysInfo.used_memory_bytes;
     const memoryUsagePercent = totalMemoryInBytes > 0
         ? usedMemoryInBytes / totalMemoryInBytes * 100
         : 0;
@@ -167,8 +140,8 @@ export default function System({sysInfo, disks}: { sysInfo: SystemInfo, disks: D
             <div className="flex flex-col gap-2 p-6">
                 {disks.map((disk, index) => {
                     // Calculate storage usage percent for all disks
-                    const totalStorageInBytes = memoryStringToBytes(disk.total_space);
-                    const usedStorageInBytes = memoryStringToBytes(disk.used_space);
+                    const totalStorageInBytes = disk.total_space_bytes;
+                    const usedStorageInBytes = disk.used_space_bytes;
                     const storageUsagePercent = totalStorageInBytes > 0
                         ? usedStorageInBytes / totalStorageInBytes * 100
                         : 0;
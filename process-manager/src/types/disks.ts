@@ -0,0 +1,18 @@
+import {DiskInfo} from "./system.ts";
+
+export interface DiskIoInfo {
+    device: string,
+    read_bytes_per_sec: number,
+    written_bytes_per_sec: number,
+    reads_per_sec: number,
+    writes_per_sec: number,
+    busy_percent: number,
+    read_bytes: number,
+    written_bytes: number,
+}
+
+// Payload of `disk_update` events
+export interface DiskUpdate {
+    disks: DiskInfo[],
+    io: DiskIoInfo[],
+}
@@ -0,0 +1,30 @@
+export type FileDescriptorKind =
+    | "file"
+    | "directory"
+    | "socket"
+    | "pipe"
+    | "char_device"
+    | "block_device"
+    | "event_fd"
+    | "epoll"
+    | "signal_fd"
+    | "timer_fd"
+    | "inotify"
+    | "anon_inode"
+    | "other";
+
+export interface FileDescriptor {
+    fd: number,
+    target: string,
+    kind: FileDescriptorKind,
+    position: number | null,
+    flags: number | null,
+    flag_names: string[],
+}
+
+export interface OpenFiles {
+    id: string,
+    descriptors: FileDescriptor[],
+    soft_limit: number | null,
+    hard_limit: number | null,
+}
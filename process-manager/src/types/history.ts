@@ -0,0 +1,19 @@
+export type Metric = "cpu_usage" | "memory_used" | "network_received" | "network_transmitted";
+
+export interface HistoryTier {
+    resolution_ms: number,
+    retention_ms: number,
+}
+
+export interface TimeRange {
+    start_ms: number,
+    end_ms?: number,
+}
+
+export interface HistoryPoint {
+    timestamp_ms: number,
+    avg: number,
+    min: number,
+    max: number,
+    samples: number,
+}
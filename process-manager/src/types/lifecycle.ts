@@ -0,0 +1,22 @@
+export interface ExitInfo {
+    code: number | null,
+    signal: number | null,
+}
+
+export interface LifecycleRecord {
+    event: "started" | "exited",
+    id: string,
+    parent_id: string | null,
+    name: string,
+    command_line: string,
+    start_time: number,
+    lifetime_secs: number,
+    exit: ExitInfo | null,
+}
+
+// Payload of `process_lifecycle` events
+export interface LifecycleBatch {
+    records: LifecycleRecord[],
+    dropped_started: number,
+    dropped_exited: number,
+}
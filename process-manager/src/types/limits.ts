@@ -0,0 +1,33 @@
+export type Resource =
+    | "cpu_time"
+    | "file_size"
+    | "data_size"
+    | "stack_size"
+    | "core_file_size"
+    | "resident_set"
+    | "processes"
+    | "open_files"
+    | "locked_memory"
+    | "address_space"
+    | "file_locks"
+    | "pending_signals"
+    | "msgqueue_size"
+    | "nice_priority"
+    | "realtime_priority"
+    | "realtime_timeout";
+
+export type LimitValue = number | "unlimited";
+
+export interface LimitPair {
+    soft: LimitValue,
+    hard: LimitValue,
+}
+
+export interface ResourceLimitInfo {
+    resource: Resource,
+    soft: LimitValue,
+    hard: LimitValue,
+    unit: string,
+    usage: number | null,
+    near_limit: boolean,
+}
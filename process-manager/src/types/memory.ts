@@ -0,0 +1,33 @@
+export interface MemoryUsage {
+    size_bytes: number,
+    rss_bytes: number,
+    pss_bytes: number,
+    uss_bytes: number,
+    shared_clean_bytes: number,
+    shared_dirty_bytes: number,
+    private_clean_bytes: number,
+    private_dirty_bytes: number,
+    swap_bytes: number,
+}
+
+export type MappingKind = "file" | "heap" | "stack" | "anonymous" | "special";
+
+export interface MemoryMapping {
+    address_range: string,
+    permissions: string,
+    offset: number,
+    usage: MemoryUsage,
+}
+
+export interface MappingGroup {
+    name: string,
+    kind: MappingKind,
+    usage: MemoryUsage,
+    mappings: MemoryMapping[],
+}
+
+export interface MemoryMap {
+    id: string,
+    total: MemoryUsage,
+    groups: MappingGroup[],
+}
@@ -0,0 +1,17 @@
+export type MonitorKind = "processes" | "system" | "lifecycle" | "threads" | "network" | "disks";
+
+export interface MonitorStatus {
+    id: number,
+    kind: MonitorKind,
+    state: "running" | "paused",
+    interval_ms: number,
+    effective_interval_ms: number,
+    adaptive: boolean,
+}
+
+// Event payload shape when a monitor is started with `payload_version: 2`
+export interface VersionedPayload<T> {
+    version: number,
+    timestamp_ms: number,
+    data: T,
+}
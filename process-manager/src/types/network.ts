@@ -0,0 +1,19 @@
+export interface NetworkInterfaceInfo {
+    name: string,
+    mac_address: string,
+    ip_addresses: string[],
+    mtu: number,
+    loopback: boolean,
+    received_bytes_per_sec: number,
+    transmitted_bytes_per_sec: number,
+    received_packets_per_sec: number,
+    transmitted_packets_per_sec: number,
+    received_bytes: number,
+    transmitted_bytes: number,
+    received_packets: number,
+    transmitted_packets: number,
+    receive_errors: number,
+    transmit_errors: number,
+    receive_drops: number | null,
+    transmit_drops: number | null,
+}
@@ -0,0 +1,6 @@
+export type IoPriorityClass = "none" | "real_time" | "best_effort" | "idle";
+
+export interface IoPriority {
+    class: IoPriorityClass,
+    level: number,
+}
@@ -1,9 +1,126 @@
+import {ResourceLimitInfo} from "./limits.ts";
+
 export interface ProcessInfo {
     id: string,
+    parent_id: string | null,
     name: string,
     owner: string,
     running_time_formatted: string,
     memory_used: string,
     status: string,
     cpu_usage_percent: number,
-}
\ No newline at end of file
+    memory_bytes: number,
+    run_time_secs: number,
+    start_time: number,
+    disk_read_bytes_per_sec: number,
+    disk_written_bytes_per_sec: number,
+    disk_read_bytes: number,
+    disk_written_bytes: number,
+    // Only present when requested through `ProcessColumn`s
+    pss_bytes?: number,
+    uss_bytes?: number,
+    socket_count?: number,
+}
+
+export interface ProcessNode extends ProcessInfo {
+    depth: number,
+    subtree_cpu_usage_percent: number,
+    subtree_memory_used: string,
+    subtree_memory_bytes: number,
+    children: ProcessNode[],
+}
+
+export type KillScope = "tree" | "process_group" | "session";
+
+export interface KillReport {
+    id: string,
+    name: string,
+    signal_error: string | null,
+    survived: boolean,
+}
+
+export interface TextMatch {
+    pattern: string,
+    regex?: boolean,
+    case_sensitive?: boolean,
+}
+
+export interface ProcessFilter {
+    id?: TextMatch,
+    name?: TextMatch,
+    owner?: TextMatch,
+    statuses?: string[],
+    min_cpu_usage_percent?: number,
+    max_cpu_usage_percent?: number,
+    min_memory_bytes?: number,
+    max_memory_bytes?: number,
+}
+
+export type SortField = "id" | "name" | "owner" | "status" | "cpu_usage" | "memory" | "running_time" | "io";
+
+export type ProcessColumn = "pss" | "uss" | "sockets";
+
+export interface ProcessSort {
+    field: SortField,
+    direction: "asc" | "desc",
+}
+
+// Argument of `monitor_processes` and `update_process_query`. Fields left out keep their current
+// value
+export interface ProcessQueryUpdate {
+    filter?: ProcessFilter,
+    sort?: ProcessSort,
+    columns?: ProcessColumn[],
+}
+
+export type ProcessChange = Partial<Omit<ProcessInfo, "id" | "parent_id" | "start_time">> & { id: string };
+
+// Payload of `process_list_delta` events and the `resync` command
+export type ProcessListEvent =
+    | { type: "snapshot", sequence: number, processes: ProcessInfo[] }
+    | {
+        type: "delta",
+        sequence: number,
+        removed: string[],
+        added: ProcessInfo[],
+        changed: ProcessChange[],
+        order?: string[],
+    };
+
+export interface EnvVar {
+    name: string,
+    value: string,
+}
+
+// Fields that couldn't be read are null and explained in `errors`, keyed by field name
+export interface ProcessDetails {
+    id: string,
+    name: string,
+    command_line: string[] | null,
+    exe: string | null,
+    cwd: string | null,
+    root: string | null,
+    environment: EnvVar[] | null,
+    status: string,
+    start_time: number,
+    run_time_secs: number,
+    parent_id: string | null,
+    session_id: string | null,
+    process_group_id: string | null,
+    real_user_id: string | null,
+    effective_user_id: string | null,
+    real_group_id: string | null,
+    effective_group_id: string | null,
+    thread_count: number | null,
+    nice: number | null,
+    priority: number | null,
+    virtual_memory_bytes: number,
+    resident_memory_bytes: number,
+    cpu_time_ms: number,
+    disk_read_bytes: number | null,
+    disk_written_bytes: number | null,
+    open_file_count: number | null,
+    socket_count: number | null,
+    resource_limits: ResourceLimitInfo[] | null,
+    errors: Record<string, string>,
+}
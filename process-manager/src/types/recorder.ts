@@ -0,0 +1,41 @@
+export interface RecorderOptions {
+    sample_interval_ms?: number,
+    retention_ms?: number,
+    min_cpu_usage_percent?: number,
+    max_processes_per_sample?: number,
+}
+
+export interface RecordingSession {
+    id: number,
+    started_at_ms: number,
+    ended_at_ms: number | null,
+    system_samples: number,
+    process_samples: number,
+}
+
+export interface RecordedSystemSample {
+    session_id: number,
+    timestamp_ms: number,
+    cpu_usage_percent: number,
+    total_memory_bytes: number,
+    used_memory_bytes: number,
+}
+
+export interface RecordedProcessSample {
+    session_id: number,
+    timestamp_ms: number,
+    id: string,
+    parent_id: string | null,
+    name: string,
+    owner: string,
+    status: string,
+    cpu_usage_percent: number,
+    memory_bytes: number,
+    start_time: number,
+}
+
+export interface PruneReport {
+    sessions: number,
+    system_samples: number,
+    process_samples: number,
+}
@@ -0,0 +1,6 @@
+// Value of a process or thread setting before and after a change. Pass `before` back to undo it
+export interface SettingChange<T> {
+    id: string,
+    before: T,
+    after: T,
+}
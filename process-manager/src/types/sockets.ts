@@ -0,0 +1,19 @@
+export type SocketProtocol = "tcp" | "tcp6" | "udp" | "udp6" | "unix";
+
+export type PortProtocol = "tcp" | "udp";
+
+export interface SocketInfo {
+    protocol: SocketProtocol,
+    local_address: string,
+    local_port: number | null,
+    remote_address: string | null,
+    remote_port: number | null,
+    state: string,
+    inode: number,
+}
+
+export interface PortOwner {
+    id: string,
+    name: string,
+    socket: SocketInfo,
+}
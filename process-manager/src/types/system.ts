@@ -5,10 +5,65 @@ export interface SystemInfo {
     cpu_usage_percent: number,
     total_memory: string,
     used_memory: string,
+    available_memory: string,
+    total_swap: string,
+    used_swap: string,
+    total_memory_bytes: number,
+    used_memory_bytes: number,
+    available_memory_bytes: number,
+    total_swap_bytes: number,
+    used_swap_bytes: number,
+    memory_breakdown: MemoryBreakdown | null,
+    cpu_brand: string,
+    cpu_vendor: string,
+    physical_core_count: number | null,
+    logical_core_count: number,
+    cores: CoreInfo[],
+    load_average: LoadAverage,
 }
 
+export interface CoreInfo {
+    name: string,
+    usage_percent: number,
+    frequency_mhz: number,
+    max_frequency_mhz: number | null,
+}
+
+export interface MemoryBreakdown {
+    cached_bytes: number,
+    buffers_bytes: number,
+    shared_bytes: number,
+    slab_bytes: number,
+    slab_reclaimable_bytes: number,
+    dirty_bytes: number,
+    writeback_bytes: number,
+    anon_huge_pages_bytes: number,
+    huge_pages_total: number,
+    huge_pages_free: number,
+    huge_page_size_bytes: number,
+    committed_bytes: number,
+    commit_limit_bytes: number,
+}
+
+export interface LoadAverage {
+    one: number,
+    five: number,
+    fifteen: number,
+}
+
+export type DiskKind = "ssd" | "hdd" | "unknown";
+
 export interface DiskInfo {
     name: string,
+    mount_point: string,
+    file_system: string,
+    kind: DiskKind,
+    removable: boolean,
+    read_only: boolean,
     total_space: string,
     used_space: string,
+    total_space_bytes: number,
+    used_space_bytes: number,
+    total_inodes: number | null,
+    used_inodes: number | null,
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+export interface ThreadInfo {
+    id: string,
+    name: string,
+    status: string,
+    cpu_usage_percent: number,
+    cpu_time_ms: number,
+    processor: number,
+    nice: number,
+}
+
+// Payload of `thread_update` events
+export interface ThreadList {
+    id: string,
+    threads: ThreadInfo[],
+}